@@ -0,0 +1,275 @@
+use std::{
+    cell::RefCell,
+    collections::{BTreeSet, HashMap},
+    error::Error,
+    path::{Path, PathBuf},
+    rc::Rc,
+};
+
+use crate::{
+    environment,
+    package_json::{self, PACKAGE_JSON_FILE_NAME},
+    root::find_package_json_directory,
+    violations::UnusedDependency,
+};
+
+/// Directory names whose files are considered tests.
+const TEST_DIRECTORIES: &[&str] = &["__tests__", "__mocks__", "test", "tests"];
+
+/// The dependencies declared by a package.json file.
+pub struct DeclaredDependencies {
+    pub package_directory: PathBuf,
+    /// Path of the package.json file, relative to the root directory.
+    pub package_json_path: String,
+    name: Option<String>,
+    /// Names of `dependencies`, `peerDependencies` and
+    /// `optionalDependencies`.
+    dependencies: BTreeSet<String>,
+    dev_dependencies: BTreeSet<String>,
+    /// Names of `dependencies` only, which should all be imported.
+    runtime_dependencies: BTreeSet<String>,
+}
+
+/// How a package is declared by a package.json file.
+#[derive(Debug, PartialEq)]
+pub enum Declaration {
+    Dependency,
+    DevDependency,
+    Undeclared,
+}
+
+impl DeclaredDependencies {
+    /// Returns how the passed-in package, or for type-only imports its
+    /// DefinitelyTyped `@types` package, is declared.
+    pub fn declaration(&self, package: &str, type_only: bool) -> Declaration {
+        if self.name.as_deref() == Some(package) {
+            // Packages may import themselves by name.
+            return Declaration::Dependency;
+        }
+        let types_package = types_package(package);
+        let names = if type_only {
+            vec![package, types_package.as_str()]
+        } else {
+            vec![package]
+        };
+        if names.iter().any(|name| self.dependencies.contains(*name)) {
+            Declaration::Dependency
+        } else if names
+            .iter()
+            .any(|name| self.dev_dependencies.contains(*name))
+        {
+            Declaration::DevDependency
+        } else {
+            Declaration::Undeclared
+        }
+    }
+}
+
+/// The dependencies declared by the package.json nearest to each visited
+/// directory, read once per package, along with the packages that its
+/// files import.
+#[derive(Default)]
+pub struct DependencyCache {
+    by_package_directory: RefCell<HashMap<PathBuf, Rc<DeclaredDependencies>>>,
+    imported_by_package_directory: RefCell<HashMap<PathBuf, BTreeSet<String>>>,
+}
+
+impl DependencyCache {
+    /// Returns the dependencies declared by the package.json nearest to the
+    /// passed-in directory, if any.
+    pub fn get(
+        &self,
+        root: &Path,
+        directory: &Path,
+    ) -> Result<Option<Rc<DeclaredDependencies>>, Box<dyn Error>> {
+        let Some(package_directory) = find_package_json_directory(directory) else {
+            return Ok(None);
+        };
+        if let Some(declared) = self.by_package_directory.borrow().get(&package_directory) {
+            return Ok(Some(declared.clone()));
+        }
+
+        let package_json = package_json::read_package_json(&package_directory)?;
+        let package_json_path = package_directory.join(PACKAGE_JSON_FILE_NAME);
+        let declared = Rc::new(DeclaredDependencies {
+            package_directory: package_directory.clone(),
+            package_json_path: package_json_path
+                .strip_prefix(root)
+                .unwrap_or(&package_json_path)
+                .to_str()
+                .expect("")
+                .to_string(),
+            name: package_json.name,
+            runtime_dependencies: package_json.dependencies.keys().cloned().collect(),
+            dependencies: package_json
+                .dependencies
+                .into_keys()
+                .chain(package_json.peer_dependencies.into_keys())
+                .chain(package_json.optional_dependencies.into_keys())
+                .collect(),
+            dev_dependencies: package_json.dev_dependencies.into_keys().collect(),
+        });
+        self.by_package_directory
+            .borrow_mut()
+            .insert(package_directory, declared.clone());
+        Ok(Some(declared))
+    }
+
+    /// Records that a file of the passed-in package imports the package
+    /// named by the specifier, which also uses its `@types` package.
+    pub fn record_import(&self, declared: &DeclaredDependencies, specifier: &str, package: &str) {
+        let mut imported_by_package_directory = self.imported_by_package_directory.borrow_mut();
+        let imported = imported_by_package_directory
+            .entry(declared.package_directory.clone())
+            .or_default();
+        if environment::node_builtin(specifier).is_some() {
+            imported.insert("@types/node".to_string());
+        } else {
+            imported.insert(types_package(package));
+            imported.insert(package.to_string());
+        }
+    }
+
+    /// Returns the `dependencies` that no visited file imports, of the
+    /// package.json files within the passed-in target directory, whose files
+    /// have all been visited.
+    pub fn list_unused_dependencies(&self, target: &Path) -> Vec<UnusedDependency> {
+        let imported_by_package_directory = self.imported_by_package_directory.borrow();
+        let mut unused = vec![];
+        for (package_directory, declared) in self.by_package_directory.borrow().iter() {
+            if !package_directory.starts_with(target) {
+                continue;
+            }
+            let imported = imported_by_package_directory.get(package_directory);
+            for dependency in &declared.runtime_dependencies {
+                if !imported.is_some_and(|imported| imported.contains(dependency)) {
+                    unused.push(UnusedDependency {
+                        file_path: declared.package_json_path.clone(),
+                        package: dependency.clone(),
+                    });
+                }
+            }
+        }
+        unused
+    }
+}
+
+/// Returns the DefinitelyTyped package of the passed-in package, like
+/// `@types/scope__pkg` for `@scope/pkg`.
+fn types_package(package: &str) -> String {
+    format!(
+        "@types/{}",
+        package.trim_start_matches('@').replace('/', "__")
+    )
+}
+
+/// Returns whether the file at the passed-in path is a test, which may
+/// import devDependencies.
+pub fn is_test_file(path: &Path) -> bool {
+    let file_name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+    file_name.contains(".test.")
+        || file_name.contains(".spec.")
+        || path
+            .parent()
+            .into_iter()
+            .flat_map(Path::components)
+            .any(|c| TEST_DIRECTORIES.contains(&c.as_os_str().to_str().unwrap_or("")))
+}
+
+#[test]
+fn test_declaration() {
+    let declared = DeclaredDependencies {
+        package_directory: PathBuf::from("/repo"),
+        package_json_path: "package.json".to_string(),
+        name: Some("@loop/billing".to_string()),
+        dependencies: BTreeSet::from(["lodash".to_string(), "@types/aws-lambda".to_string()]),
+        dev_dependencies: BTreeSet::from(["vitest".to_string(), "@types/scope__pkg".to_string()]),
+        runtime_dependencies: BTreeSet::from(["lodash".to_string()]),
+    };
+    assert_eq!(
+        declared.declaration("lodash", false),
+        Declaration::Dependency
+    );
+    assert_eq!(
+        declared.declaration("@loop/billing", false),
+        Declaration::Dependency
+    );
+    assert_eq!(
+        declared.declaration("vitest", false),
+        Declaration::DevDependency
+    );
+    assert_eq!(
+        declared.declaration("axios", false),
+        Declaration::Undeclared
+    );
+    assert_eq!(
+        declared.declaration("aws-lambda", false),
+        Declaration::Undeclared
+    );
+    assert_eq!(
+        declared.declaration("aws-lambda", true),
+        Declaration::Dependency
+    );
+    assert_eq!(
+        declared.declaration("@scope/pkg", true),
+        Declaration::DevDependency
+    );
+}
+
+#[test]
+fn test_is_test_file() {
+    assert!(is_test_file(Path::new("src/billing/ledger.test.ts")));
+    assert!(is_test_file(Path::new("src/billing/ledger.spec.tsx")));
+    assert!(is_test_file(Path::new("src/billing/__tests__/ledger.ts")));
+    assert!(is_test_file(Path::new("test/setup.ts")));
+    assert!(!is_test_file(Path::new("src/billing/ledger.ts")));
+    assert!(!is_test_file(Path::new("src/testing/ledger.ts")));
+}
+
+#[test]
+fn test_list_unused_dependencies() {
+    let dir = crate::test_utils::TempDir::new("unused");
+    dir.write(
+        PACKAGE_JSON_FILE_NAME,
+        r#"{"dependencies": {"lodash": "4", "@types/lodash": "4", "@types/node": "20", "@scope/pkg": "1", "@types/scope__pkg": "1", "axios": "1"}, "devDependencies": {"vitest": "1"}}"#,
+    );
+    dir.write(
+        &format!("packages/ledger/{}", PACKAGE_JSON_FILE_NAME),
+        r#"{"dependencies": {"left-pad": "1"}}"#,
+    );
+
+    let cache = DependencyCache::default();
+    let declared = cache.get(&dir, &dir).unwrap().unwrap();
+    cache.get(&dir, &dir.join("packages/ledger")).unwrap();
+    cache.record_import(&declared, "lodash/fp", "lodash");
+    cache.record_import(&declared, "@scope/pkg/client", "@scope/pkg");
+    // Builtins are typed by `@types/node`.
+    cache.record_import(&declared, "node:fs", "node:fs");
+
+    let unused = |target: &Path| {
+        let mut unused = cache
+            .list_unused_dependencies(target)
+            .into_iter()
+            .map(|unused| (unused.file_path, unused.package))
+            .collect::<Vec<_>>();
+        unused.sort();
+        unused
+    };
+    assert_eq!(
+        unused(&dir),
+        vec![
+            ("package.json".to_string(), "axios".to_string()),
+            (
+                "packages/ledger/package.json".to_string(),
+                "left-pad".to_string()
+            ),
+        ]
+    );
+    assert_eq!(
+        unused(&dir.join("packages/ledger")),
+        vec![(
+            "packages/ledger/package.json".to_string(),
+            "left-pad".to_string()
+        )]
+    );
+}
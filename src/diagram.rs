@@ -9,7 +9,8 @@ use std::{
     path::Path,
 };
 
-use crate::rules::read_rules_file;
+use crate::files;
+use crate::rules::{expand_names, read_rules_file, Rules};
 use crate::RULES_FILE_NAME;
 
 type AllowsMap<T> = BTreeMap<T, BTreeSet<T>>;
@@ -37,14 +38,60 @@ pub fn update_diagrams_recursively(
     Ok(())
 }
 
-fn get_allows(yaml_path: &Path) -> Result<AllowsMap<String>, Box<dyn Error>> {
-    let yaml_rules = read_rules_file(yaml_path)?;
-    let converted_rules = yaml_rules
-        .allow
-        .into_iter()
-        .map(|(source, targets)| (source, BTreeSet::from_iter(targets)))
-        .collect::<AllowsMap<_>>();
-    Ok(converted_rules)
+/// The imports that a rules file allows, with glob patterns like `feature-*`
+/// expanded to the child directories they match.
+#[derive(Debug, PartialEq)]
+struct DiagramRules {
+    /// The children that each child may import through `allow`.
+    allows: AllowsMap<String>,
+    /// The children of each layer, from top to bottom.
+    layers: Vec<(String, Vec<String>)>,
+    /// The children that each child may import through `allow` or `layers`.
+    all_allows: AllowsMap<String>,
+}
+
+fn get_diagram_rules(rules: &Rules, children: &[String]) -> DiagramRules {
+    let mut allows = AllowsMap::new();
+    for (source, targets) in &rules.allow {
+        let targets = expand_names(targets, children);
+        for source in expand_names([source], children) {
+            allows
+                .entry(source.to_string())
+                .or_insert_with(BTreeSet::new)
+                .extend(
+                    targets
+                        .iter()
+                        .filter(|target| **target != "-" && **target != source)
+                        .map(|target| target.to_string()),
+                );
+        }
+    }
+    let layers = rules
+        .layers
+        .iter()
+        .map(|layer| {
+            let children = expand_names([layer], children);
+            (
+                layer.clone(),
+                children.into_iter().map(str::to_string).collect(),
+            )
+        })
+        .collect();
+    let mut all_allows = allows.clone();
+    for (layer, below) in rules.layer_allows() {
+        let below = expand_names(below, children);
+        for source in expand_names([layer], children) {
+            all_allows
+                .entry(source.to_string())
+                .or_default()
+                .extend(below.iter().map(|target| target.to_string()));
+        }
+    }
+    DiagramRules {
+        allows,
+        layers,
+        all_allows,
+    }
 }
 
 fn get_transitive_allows<T>(direct_allows: &AllowsMap<T>) -> AllowsMap<&T>
@@ -134,20 +181,51 @@ pub fn update_readme_with_diagram(
     readme_path: &Path,
     show_circular_dependencies: bool,
 ) -> Result<(), Box<dyn Error>> {
-    let allows = get_allows(yaml_path)?;
-    if allows.is_empty() {
+    let rules = read_rules_file(yaml_path)?;
+    let dir = yaml_path.parent().unwrap_or(Path::new("."));
+    let children = files::list_files_and_directories(dir)?.directories;
+    let diagram_rules = get_diagram_rules(&rules, &children);
+    if diagram_rules.allows.is_empty() && diagram_rules.layers.is_empty() {
         return Ok(());
     }
-    let transitive_allows: AllowsMap<_> = get_transitive_allows(&allows);
     let (before_dep_diagram_block, after_dep_diagram_block) = get_other_readme_lines(readme_path)?;
 
+    let mut output_lines = Vec::new();
+    output_lines.extend(before_dep_diagram_block);
+    output_lines.extend(get_mermaid_lines(
+        &diagram_rules,
+        show_circular_dependencies,
+    ));
+    output_lines.extend(after_dep_diagram_block);
+
+    // Add a newline to the end of the file if it doesn't already have one.
+    if !output_lines.is_empty() {
+        let last_line = output_lines.last().unwrap();
+        if !last_line.is_empty() {
+            output_lines.push("".to_string());
+        }
+    }
+
+    let output_content = output_lines.join("\n");
+
+    let mut file = fs::File::create(readme_path)?;
+    file.write_all(output_content.as_bytes())?;
+
+    Ok(())
+}
+
+/// Returns the Mermaid code block of the diagram. Each layer is drawn as a
+/// subgraph of its children, linked to the layer below it.
+fn get_mermaid_lines(
+    diagram_rules: &DiagramRules,
+    show_circular_dependencies: bool,
+) -> Vec<String> {
+    let transitive_allows: AllowsMap<_> = get_transitive_allows(&diagram_rules.all_allows);
+
     let mut circular_edge_indices = vec![];
     let mut mermaid_edges = vec![];
-    for (source, targets) in &allows {
+    for (source, targets) in &diagram_rules.allows {
         for target in targets {
-            if target == "-" {
-                continue;
-            }
             let is_circular_dependency = transitive_allows
                 .get(target)
                 .map(|deps| deps.contains(source))
@@ -159,13 +237,23 @@ pub fn update_readme_with_diagram(
         }
     }
 
-    let mut output_lines = Vec::new();
-    output_lines.extend(before_dep_diagram_block);
-    output_lines.push("```mermaid".to_string());
-    output_lines.push("%%dep".to_string());
-    output_lines.push("graph TD".to_string());
-    output_lines.push("  subgraph \" \"".to_string());
+    let mut output_lines = vec![
+        "```mermaid".to_string(),
+        "%%dep".to_string(),
+        "graph TD".to_string(),
+        "  subgraph \" \"".to_string(),
+    ];
+    for (index, (layer, children)) in diagram_rules.layers.iter().enumerate() {
+        output_lines.push(format!("  subgraph layer_{} [\"{}\"]", index, layer));
+        output_lines.extend(children.iter().map(|child| format!("    {}", child)));
+        output_lines.push("  end".to_string());
+    }
     output_lines.extend(mermaid_edges);
+    // Link the layers after the edges so that the indices in `linkStyle`
+    // stay those of the edges.
+    for index in 1..diagram_rules.layers.len() {
+        output_lines.push(format!("  layer_{} --> layer_{}", index - 1, index));
+    }
     output_lines.push("  end".to_string());
     if show_circular_dependencies && !circular_edge_indices.is_empty() {
         output_lines.push(format!(
@@ -174,20 +262,47 @@ pub fn update_readme_with_diagram(
         ));
     }
     output_lines.push("```".to_string());
-    output_lines.extend(after_dep_diagram_block);
-
-    // Add a newline to the end of the file if it doesn't already have one.
-    if !output_lines.is_empty() {
-        let last_line = output_lines.last().unwrap();
-        if !last_line.is_empty() {
-            output_lines.push("".to_string());
-        }
-    }
-
-    let output_content = output_lines.join("\n");
-
-    let mut file = fs::File::create(readme_path)?;
-    file.write_all(output_content.as_bytes())?;
+    output_lines
+}
 
-    Ok(())
+#[test]
+fn test_get_mermaid_lines() {
+    let rules: Rules = serde_yaml::from_str(
+        "
+allow:
+  feature-*: [shared]
+  shared: [ui]
+layers: [ui, feature-*, shared]
+",
+    )
+    .unwrap();
+    let children = ["feature-a", "feature-b", "shared", "ui"].map(String::from);
+    let diagram_rules = get_diagram_rules(&rules, &children);
+    assert_eq!(
+        get_mermaid_lines(&diagram_rules, true),
+        [
+            "```mermaid",
+            "%%dep",
+            "graph TD",
+            "  subgraph \" \"",
+            "  subgraph layer_0 [\"ui\"]",
+            "    ui",
+            "  end",
+            "  subgraph layer_1 [\"feature-*\"]",
+            "    feature-a",
+            "    feature-b",
+            "  end",
+            "  subgraph layer_2 [\"shared\"]",
+            "    shared",
+            "  end",
+            "  feature-a --> shared",
+            "  feature-b --> shared",
+            "  shared --> ui",
+            "  layer_0 --> layer_1",
+            "  layer_1 --> layer_2",
+            "  end",
+            "linkStyle 0,1,2 stroke:red;",
+            "```",
+        ]
+    );
 }
@@ -1,48 +1,143 @@
-use std::path::Path;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
 
-use crate::rules::{self, Rules};
+use crate::{
+    environment::Environment,
+    files,
+    packages::PackageRules,
+    rules::{self, Rules},
+};
 
-pub fn get_initial_disallowed_imports(root: &Path, target: &Path) -> Vec<String> {
-    get_initial_disallowed_imports_impl(root, target, vec![], root)
+/// Root-relative path prefixes that code in a directory is disallowed to
+/// import. Type-only imports are checked against their own, more
+/// permissive, list.
+#[derive(Clone, Default)]
+pub struct DisallowedImports {
+    pub value_imports: Vec<String>,
+    pub type_imports: Vec<String>,
+    /// Absolute directories outside of the root that may be imported
+    /// nonetheless.
+    pub allowed_external_roots: Vec<PathBuf>,
+    /// Package rules of the directory and its ancestors, which third-party
+    /// imports must all satisfy.
+    pub package_rules: Vec<PackageRules>,
+    /// The environment declared by the nearest rules file, if any.
+    pub environment: Option<Environment>,
+}
+
+impl DisallowedImports {
+    pub fn for_import(&self, type_only: bool) -> &[String] {
+        if type_only {
+            &self.type_imports
+        } else {
+            &self.value_imports
+        }
+    }
+
+    pub fn allows_package(&self, package: &str) -> bool {
+        self.package_rules.iter().all(|rules| rules.allows(package))
+    }
+
+    /// Returns whether the passed-in absolute path outside of the root may
+    /// be imported.
+    pub fn allows_external(&self, path: &Path) -> bool {
+        self.allowed_external_roots
+            .iter()
+            .any(|root| path.starts_with(root))
+    }
+}
+
+pub fn get_initial_disallowed_imports(root: &Path, target: &Path) -> DisallowedImports {
+    get_initial_disallowed_imports_impl(root, target, DisallowedImports::default(), root)
+}
+
+/// Returns the disallowed imports of the current directory, extended with
+/// the rules of the directory that apply to the directory itself.
+pub fn get_dir_disallowed_imports(
+    current: &Path,
+    disallowed_imports: &DisallowedImports,
+    rules: &Option<Rules>,
+) -> DisallowedImports {
+    let mut dir_disallowed_imports = disallowed_imports.clone();
+    if let Some(rules) = rules {
+        dir_disallowed_imports.allowed_external_roots.extend(
+            rules
+                .allow_external
+                .iter()
+                .filter_map(|external| fs::canonicalize(current.join(external)).ok()),
+        );
+        if rules.environment.is_some() {
+            dir_disallowed_imports.environment = rules.environment;
+        }
+    }
+    dir_disallowed_imports
 }
 
 pub fn get_child_disallowed_imports(
     root: &Path,
     current: &Path,
-    disallowed_imports: &[String],
+    disallowed_imports: &DisallowedImports,
     rules: &Option<Rules>,
     directory: &str,
-) -> Vec<String> {
-    let mut dir_disallowed_imports = disallowed_imports.to_owned();
+    siblings: &[String],
+) -> DisallowedImports {
+    let mut dir_disallowed_imports = disallowed_imports.clone();
     if let Some(rules) = rules {
-        if let Some(disallowed_siblings) = rules.get_disallowed_siblings(directory) {
-            let new_disallowed_imports = disallowed_siblings
-                .iter()
-                .map(|s| current.join(s))
-                .filter_map(|p| p.strip_prefix(root).ok().map(|p| p.to_path_buf()))
-                .map(|p| {
-                    let mut r = p.to_str().expect("").to_string();
-                    // Include trailing slash. Say:
-                    // src/foo/ is disallowed
-                    // src/foo-bar/ is allowed
-                    // Without the trailing slash, we'd incorrectly
-                    // disallow foo-bar since it would match src/foo.
-                    r.push('/');
-                    r
-                })
-                .collect::<Vec<_>>();
-            dir_disallowed_imports.extend(new_disallowed_imports);
+        if let Some(disallowed_siblings) = rules.get_disallowed_siblings(directory, siblings, false)
+        {
+            dir_disallowed_imports
+                .value_imports
+                .extend(to_root_relative_prefixes(
+                    root,
+                    current,
+                    &disallowed_siblings,
+                ));
+        }
+        if let Some(disallowed_siblings) = rules.get_disallowed_siblings(directory, siblings, true)
+        {
+            dir_disallowed_imports
+                .type_imports
+                .extend(to_root_relative_prefixes(
+                    root,
+                    current,
+                    &disallowed_siblings,
+                ));
+        }
+        if let Some(package_rules) = rules.packages.get(directory) {
+            dir_disallowed_imports
+                .package_rules
+                .push(package_rules.clone());
         }
     }
     dir_disallowed_imports
 }
 
+fn to_root_relative_prefixes(root: &Path, current: &Path, siblings: &[&str]) -> Vec<String> {
+    siblings
+        .iter()
+        .map(|s| current.join(s))
+        .filter_map(|p| p.strip_prefix(root).ok().map(|p| p.to_path_buf()))
+        .map(|p| {
+            let mut r = p.to_str().expect("").to_string();
+            // Include trailing slash. Say:
+            // src/foo/ is disallowed
+            // src/foo-bar/ is allowed
+            // Without the trailing slash, we'd incorrectly
+            // disallow foo-bar since it would match src/foo.
+            r.push('/');
+            r
+        })
+        .collect::<Vec<_>>()
+}
+
 fn get_initial_disallowed_imports_impl(
     root: &Path,
     target: &Path,
-    disallowed_imports: Vec<String>,
+    disallowed_imports: DisallowedImports,
     current: &Path,
-) -> Vec<String> {
+) -> DisallowedImports {
     if target.eq(current) {
         return disallowed_imports;
     }
@@ -55,8 +150,18 @@ fn get_initial_disallowed_imports_impl(
         .and_then(|component| component.as_os_str().to_str().map(String::from))
         .expect("Failed to read next directory name.");
     let (rules, _) = &rules::get_dir_rules_if_exists(root, current);
-    let child_disallowed_imports =
-        get_child_disallowed_imports(root, current, &disallowed_imports, rules, &next_dir_name);
+    let disallowed_imports = get_dir_disallowed_imports(current, &disallowed_imports, rules);
+    let siblings = files::list_files_and_directories(current)
+        .map(|files_and_directories| files_and_directories.directories)
+        .unwrap_or_default();
+    let child_disallowed_imports = get_child_disallowed_imports(
+        root,
+        current,
+        &disallowed_imports,
+        rules,
+        &next_dir_name,
+        &siblings,
+    );
     get_initial_disallowed_imports_impl(
         root,
         target,
@@ -64,3 +169,25 @@ fn get_initial_disallowed_imports_impl(
         &current.join(next_dir_name),
     )
 }
+
+#[test]
+fn test_allow_external() {
+    let temp_dir = crate::test_utils::TempDir::new("external");
+    temp_dir.create_dirs(&["pkg/src", "shared", "shared-v2"]);
+    let dir = fs::canonicalize(&temp_dir).unwrap();
+    let current = dir.join("pkg/src");
+
+    let rules: Rules =
+        serde_yaml::from_str("allow_external: ['../../shared/', '../../missing']\n").unwrap();
+    let disallowed_imports =
+        get_dir_disallowed_imports(&current, &DisallowedImports::default(), &Some(rules));
+    // Entries are canonicalized, and those that don't exist are dropped.
+    assert_eq!(
+        disallowed_imports.allowed_external_roots,
+        vec![dir.join("shared")]
+    );
+    assert!(disallowed_imports.allows_external(&dir.join("shared/x.ts")));
+    assert!(!disallowed_imports.allows_external(&dir.join("shared-v2/x.ts")));
+    assert!(!disallowed_imports.allows_external(&dir.join("missing/x.ts")));
+    assert!(!DisallowedImports::default().allows_external(&dir.join("shared/x.ts")));
+}
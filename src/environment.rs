@@ -0,0 +1,109 @@
+use serde::{Deserialize, Serialize};
+
+/// Modules built into Node.js, which may also be imported with a `node:`
+/// prefix.
+const NODE_BUILTINS: &[&str] = &[
+    "assert",
+    "async_hooks",
+    "buffer",
+    "child_process",
+    "cluster",
+    "console",
+    "constants",
+    "crypto",
+    "dgram",
+    "diagnostics_channel",
+    "dns",
+    "domain",
+    "events",
+    "fs",
+    "http",
+    "http2",
+    "https",
+    "inspector",
+    "module",
+    "net",
+    "os",
+    "path",
+    "perf_hooks",
+    "process",
+    "punycode",
+    "querystring",
+    "readline",
+    "repl",
+    "stream",
+    "string_decoder",
+    "sys",
+    "timers",
+    "tls",
+    "trace_events",
+    "tty",
+    "url",
+    "util",
+    "v8",
+    "vm",
+    "wasi",
+    "worker_threads",
+    "zlib",
+];
+
+/// Node.js builtins that edge runtimes provide compatible versions of.
+const EDGE_BUILTINS: &[&str] = &["assert", "async_hooks", "buffer", "events", "util"];
+
+/// The runtime that code in a directory runs in, which determines the
+/// Node.js builtins it may import.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Environment {
+    Browser,
+    Node,
+    Edge,
+}
+
+impl Environment {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Environment::Browser => "browser",
+            Environment::Node => "node",
+            Environment::Edge => "edge",
+        }
+    }
+
+    /// Returns whether the passed-in specifier imports a Node.js builtin
+    /// that isn't available in the environment.
+    pub fn disallows(&self, specifier: &str) -> bool {
+        let Some(builtin) = node_builtin(specifier) else {
+            return false;
+        };
+        match self {
+            Environment::Browser => true,
+            Environment::Node => false,
+            Environment::Edge => !EDGE_BUILTINS.contains(&builtin),
+        }
+    }
+}
+
+/// Returns the name of the Node.js builtin that the passed-in specifier
+/// imports, like `fs` for `node:fs/promises`.
+pub fn node_builtin(specifier: &str) -> Option<&str> {
+    let (name, has_prefix) = match specifier.strip_prefix("node:") {
+        Some(name) => (name, true),
+        None => (specifier, false),
+    };
+    let name = name.split('/').next().unwrap_or_default();
+    // Builtins like `node:test` only exist with the prefix.
+    (has_prefix || NODE_BUILTINS.contains(&name)).then_some(name)
+}
+
+#[test]
+fn test_environment_disallows() {
+    assert!(Environment::Browser.disallows("fs"));
+    assert!(Environment::Browser.disallows("node:crypto"));
+    assert!(Environment::Browser.disallows("fs/promises"));
+    assert!(Environment::Browser.disallows("node:test"));
+    assert!(!Environment::Browser.disallows("react"));
+    assert!(!Environment::Browser.disallows("fsevents"));
+    assert!(!Environment::Node.disallows("child_process"));
+    assert!(Environment::Edge.disallows("child_process"));
+    assert!(!Environment::Edge.disallows("node:buffer"));
+}
@@ -1,8 +1,8 @@
-use std::path::Path;
+use std::{collections::BTreeMap, fs, ops::Range, path::Path};
 
 use crate::{
     rules::{read_rules_file, write_formatted_rules_file},
-    violations::ReferenceToNonexistentDirectory,
+    violations::{ReferenceToNonexistentDirectory, UnusedSuppression},
     DisallowedImportViolation, RULES_FILE_NAME,
 };
 
@@ -34,7 +34,12 @@ pub fn fix_violation(
         .collect::<String>();
     let rules_path = root.join(common_prefix).join(RULES_FILE_NAME);
     let mut rules = read_rules_file(&rules_path)?;
-    let mut allow = rules.allow;
+    // Type-only imports only need to be allowed for types.
+    let allow = if violation.is_type_only {
+        &mut rules.allow_types
+    } else {
+        &mut rules.allow
+    };
     let disallowed_imports = allow
         .entry(dir_after_common_prefix)
         .or_insert_with(Vec::new);
@@ -42,7 +47,6 @@ pub fn fix_violation(
     disallowed_imports.push(disallowed_after_common_prefix);
     disallowed_imports.sort();
     disallowed_imports.dedup();
-    rules.allow = allow;
     write_formatted_rules_file(&rules_path, rules)
 }
 
@@ -52,22 +56,108 @@ pub fn remove_reference_to_nonexistent_directory(
 ) -> Result<(), Box<dyn std::error::Error>> {
     let rules_file_path = root.join(&issue.file_path);
     let mut rules = read_rules_file(&rules_file_path)?;
-    rules.allow = rules
-        .allow
+    rules.allow = remove_directory(rules.allow, &issue.directory_name);
+    rules.allow_types = remove_directory(rules.allow_types, &issue.directory_name);
+    rules.deny = remove_directory(rules.deny, &issue.directory_name);
+    rules.layers.retain(|layer| *layer != issue.directory_name);
+    rules
+        .isolate_exceptions
+        .retain(|exception| *exception != issue.directory_name);
+    rules
+        .allow_external
+        .retain(|external| *external != issue.directory_name);
+    if let Some(visibility) = &mut rules.visibility {
+        visibility.retain(|visible_to| *visible_to != issue.directory_name);
+    }
+    rules.packages.remove(&issue.directory_name);
+    write_formatted_rules_file(&rules_file_path, rules)
+}
+
+fn remove_directory(
+    allow: BTreeMap<String, Vec<String>>,
+    directory_name: &str,
+) -> BTreeMap<String, Vec<String>> {
+    allow
         .into_iter()
         .flat_map(|(source, targets)| {
-            if source == issue.directory_name {
+            if source == directory_name {
                 None
             } else {
                 Some((
                     source,
                     targets
                         .into_iter()
-                        .filter(|target| target != &issue.directory_name)
+                        .filter(|target| target != directory_name)
                         .collect(),
                 ))
             }
         })
-        .collect();
-    write_formatted_rules_file(&rules_file_path, rules)
+        .collect()
+}
+
+/// Deletes the comment of an unused suppression, and the comment ending
+/// it, along with their lines if the comments were the only thing on them.
+pub fn remove_unused_suppression(
+    root: &Path,
+    issue: &UnusedSuppression,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let file_path = root.join(&issue.file_path);
+    let mut source = fs::read_to_string(&file_path)?;
+    // The ending comment comes later, so removing it first keeps the span
+    // of the suppression valid.
+    if let Some(end_span) = &issue.end_span {
+        source = remove_comment(&source, end_span);
+    }
+    source = remove_comment(&source, &issue.span);
+    fs::write(&file_path, source)?;
+    Ok(())
+}
+
+fn remove_comment(source: &str, span: &Range<usize>) -> String {
+    let line_start = source[..span.start].rfind('\n').map(|i| i + 1).unwrap_or(0);
+    let line_end = source[span.end..]
+        .find('\n')
+        .map(|i| span.end + i + 1)
+        .unwrap_or(source.len());
+    let is_alone_on_line = source[line_start..span.start].trim().is_empty()
+        && source[span.end..line_end].trim().is_empty();
+    let (start, end) = if is_alone_on_line {
+        (line_start, line_end)
+    } else {
+        // Also drop the whitespace separating the comment from the code.
+        let start = source[..span.start].trim_end_matches([' ', '\t']).len();
+        (start, span.end)
+    };
+    format!("{}{}", &source[..start], &source[end..])
+}
+
+#[test]
+fn test_remove_unused_suppression() {
+    let dir = crate::test_utils::TempDir::new("fix");
+    // Removes the first suppression of the passed-in source.
+    let remove = |source: &str| {
+        dir.write("a.ts", source);
+        let suppression = crate::ts_reader::parse_ts_file(source).suppressions[0].clone();
+        let issue = UnusedSuppression {
+            file_path: "a.ts".to_string(),
+            line: suppression.line,
+            span: suppression.span.clone(),
+            end_span: suppression.end_span().cloned(),
+        };
+        remove_unused_suppression(&dir, &issue).unwrap();
+        fs::read_to_string(dir.join("a.ts")).unwrap()
+    };
+
+    assert_eq!(
+        remove("import a from 'a';\n  // ts_deplint-disable-next-line\nimport b from 'b';\n"),
+        "import a from 'a';\nimport b from 'b';\n"
+    );
+    assert_eq!(
+        remove("import a from 'a'; /* ts_deplint-disable */\nimport b from 'b';\n"),
+        "import a from 'a';\nimport b from 'b';\n"
+    );
+    assert_eq!(
+        remove("// ts_deplint-disable src/b\nimport b from 'b';\n// ts_deplint-enable src/b\nimport c from 'c';\n"),
+        "import b from 'b';\nimport c from 'c';\n"
+    );
 }
@@ -0,0 +1,74 @@
+/// Returns whether the passed-in `/`-separated path matches the glob
+/// pattern. `*` matches any characters within a path segment, `?` matches a
+/// single character, and a `**` segment matches any number of segments.
+pub fn glob_match(pattern: &str, path: &str) -> bool {
+    let pattern_segments = pattern.trim_matches('/').split('/').collect::<Vec<_>>();
+    let path_segments = path.trim_matches('/').split('/').collect::<Vec<_>>();
+    match_segments(&pattern_segments, &path_segments)
+}
+
+/// Returns whether the passed-in string contains glob syntax.
+pub fn is_glob(pattern: &str) -> bool {
+    pattern.contains(['*', '?'])
+}
+
+fn match_segments(pattern: &[&str], path: &[&str]) -> bool {
+    match pattern.split_first() {
+        None => path.is_empty(),
+        Some((&"**", rest)) => (0..=path.len()).any(|skip| match_segments(rest, &path[skip..])),
+        Some((first, rest)) => match path.split_first() {
+            Some((segment, path_rest)) => {
+                segment_match(first, segment) && match_segments(rest, path_rest)
+            }
+            None => false,
+        },
+    }
+}
+
+/// Returns whether a single path segment matches the glob pattern.
+pub fn segment_match(pattern: &str, segment: &str) -> bool {
+    let pattern = pattern.chars().collect::<Vec<_>>();
+    let segment = segment.chars().collect::<Vec<_>>();
+    // Index of the last `*` in the pattern and the segment index it was
+    // tried at, to backtrack to when the rest of the pattern fails.
+    let mut backtrack: Option<(usize, usize)> = None;
+    let (mut p, mut s) = (0, 0);
+    while s < segment.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, s));
+                p += 1;
+            }
+            Some(c) if *c == '?' || *c == segment[s] => {
+                p += 1;
+                s += 1;
+            }
+            _ => match backtrack {
+                Some((star_p, star_s)) => {
+                    backtrack = Some((star_p, star_s + 1));
+                    p = star_p + 1;
+                    s = star_s + 1;
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+#[test]
+fn test_glob_match() {
+    assert!(glob_match("packages/*", "packages/ledger"));
+    assert!(!glob_match("packages/*", "packages/ledger/src"));
+    assert!(glob_match("packages/**", "packages/ledger/src"));
+    assert!(glob_match("packages/**", "packages"));
+    assert!(glob_match("src/app/**", "src/app/page.ts"));
+    assert!(glob_match("**/test", "src/billing/test"));
+    assert!(glob_match("feature-*", "feature-billing"));
+    assert!(!glob_match("feature-*", "features"));
+    assert!(glob_match("@aws-sdk/*", "@aws-sdk/client-s3"));
+    assert!(glob_match("*-v?", "api-v2"));
+    assert!(glob_match("*a*b", "xaxxab"));
+    assert!(!glob_match("*a*b", "xaxxa"));
+    assert!(glob_match("*", "shared"));
+}
@@ -1,38 +1,95 @@
 use std::{error::Error, path::Path};
 
+mod dependencies;
 mod diagram;
 mod disallowed;
+mod environment;
 mod files;
 mod fix;
 mod format;
+mod glob;
+mod module_resolution;
+mod options;
+mod package_json;
+mod packages;
+mod resolve;
 mod root;
 mod rules;
+pub mod suppressions;
+#[cfg(test)]
+mod test_utils;
 pub mod ts_reader;
+mod tsconfig;
 mod violations;
+mod visibility;
 mod visit;
+mod workspace;
 
 pub use diagram::update_diagrams_recursively;
 pub use diagram::update_readme_with_diagram;
-pub use fix::{fix_violation, remove_reference_to_nonexistent_directory};
+pub use fix::{
+    fix_violation, remove_reference_to_nonexistent_directory, remove_unused_suppression,
+};
 pub use format::format_rules_file;
 pub use format::format_rules_files_recursively;
+pub use options::{LintOptions, DEFAULT_EXTENSIONS};
 pub use root::find_package_json_directory;
 pub use rules::RULES_FILE_NAME;
-pub use violations::{pretty_print_violations, DisallowedImportViolation, Violation};
+pub use violations::{
+    pretty_print_violations, DisallowedBuiltinViolation, DisallowedImportViolation,
+    DisallowedPackageViolation, ImportEscapesRoot, NonExportedImport, SuppressionWithoutReason,
+    UndeclaredDependency, UnresolvedImport, UnusedDependency, UnusedSuppression, Violation,
+    VisibilityViolation,
+};
 
 pub fn list_violations(
     root: &Path,
     target: &Path,
+    options: &LintOptions,
     abort_on_violation: bool,
 ) -> Result<Vec<violations::Violation>, Box<dyn Error>> {
     let disallowed_imports = disallowed::get_initial_disallowed_imports(root, target);
-    let mut violations = Vec::new();
-    visit::visit_path(
-        &mut violations,
+    let resolver = resolve::ImportResolver::new(root)?;
+    let dependencies = dependencies::DependencyCache::default();
+    let visibility = visibility::VisibilityCache::default();
+    let context = visit::VisitContext {
         root,
-        &disallowed_imports,
-        target,
+        options,
+        resolver: &resolver,
+        dependencies: &dependencies,
+        visibility: &visibility,
         abort_on_violation,
-    )?;
+    };
+    let mut violations = Vec::new();
+    visit::visit_path(&context, &mut violations, &disallowed_imports, target)?;
+    if options.report_unused_dependencies {
+        violations.extend(
+            dependencies
+                .list_unused_dependencies(target)
+                .into_iter()
+                .map(Violation::UnusedDependency),
+        );
+    }
     Ok(violations)
 }
+
+/// Returns the `dependencies` of package.json files within the target that
+/// no linted file imports.
+pub fn list_unused_dependencies(
+    root: &Path,
+    target: &Path,
+    options: &LintOptions,
+) -> Result<Vec<UnusedDependency>, Box<dyn Error>> {
+    let options = LintOptions {
+        report_unused_dependencies: true,
+        ..options.clone()
+    };
+    let violations = list_violations(root, target, &options, false)?;
+    Ok(violations
+        .into_iter()
+        .filter_map(|violation| match violation {
+            Violation::UnusedDependency(unused) => Some(unused),
+            _ => None,
+        })
+        .collect())
+}
@@ -1,14 +1,18 @@
-use clap::{Parser, Subcommand};
-use std::collections::HashSet;
+use clap::{Args, Parser, Subcommand};
+use std::collections::{BTreeMap, HashSet};
 use std::error::Error;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use ts_deplint::{
-    find_package_json_directory, list_violations, pretty_print_violations,
-    update_diagrams_recursively, update_readme_with_diagram, Violation, RULES_FILE_NAME,
+    find_package_json_directory, list_unused_dependencies, list_violations,
+    pretty_print_violations, update_diagrams_recursively, update_readme_with_diagram, LintOptions,
+    Violation, DEFAULT_EXTENSIONS, RULES_FILE_NAME,
 };
 
+#[cfg(test)]
+mod test_utils;
+
 #[derive(Parser)]
 #[clap(name = "ts_depslint")]
 /// ts_deplint is a tool for linting TypeScript projects for disallowed imports.
@@ -23,6 +27,7 @@ enum Commands {
     Diagram(DiagramCommand),
     Fix(FixCommand),
     Format(FormatCommand),
+    Deps(DepsCommand),
 }
 
 #[derive(Parser)]
@@ -31,6 +36,49 @@ enum Commands {
 struct LintCommand {
     /// Paths can be either directories or files.
     paths: Vec<String>,
+
+    #[command(flatten)]
+    lint_args: LintArgs,
+
+    /// Require suppression comments to give a reason after `--`.
+    #[arg(long, default_value_t = false)]
+    require_suppression_reasons: bool,
+
+    /// Report relative and root-relative imports that don't resolve to any file.
+    #[arg(long, default_value_t = false)]
+    report_unresolved_imports: bool,
+
+    /// Report `dependencies` of package.json files within the passed-in paths
+    /// that no linted file imports.
+    #[arg(long, default_value_t = false)]
+    report_unused_dependencies: bool,
+
+    /// Report imports of packages that the nearest package.json doesn't
+    /// declare. devDependencies may only be imported by tests.
+    #[arg(long, default_value_t = false)]
+    report_undeclared_dependencies: bool,
+}
+
+#[derive(Args)]
+#[clap(rename_all = "camel_case")]
+struct LintArgs {
+    /// Comma-separated extensions of the source files to lint.
+    #[arg(long, value_delimiter = ',', default_values_t = DEFAULT_EXTENSIONS.iter().map(|e| e.to_string()))]
+    extensions: Vec<String>,
+
+    /// Also lint declaration files like `foo.d.ts`.
+    #[arg(long, default_value_t = false)]
+    include_declaration_files: bool,
+}
+
+impl LintArgs {
+    fn to_options(&self) -> LintOptions {
+        LintOptions {
+            extensions: self.extensions.clone(),
+            include_declaration_files: self.include_declaration_files,
+            ..LintOptions::default()
+        }
+    }
 }
 
 #[derive(Parser)]
@@ -50,6 +98,9 @@ struct DiagramCommand {
 struct FixCommand {
     /// Paths can be either directories or files.
     paths: Vec<String>,
+
+    #[command(flatten)]
+    lint_args: LintArgs,
 }
 
 #[derive(Parser)]
@@ -60,6 +111,29 @@ struct FormatCommand {
     paths: Vec<String>,
 }
 
+#[derive(Parser)]
+/// Report on the npm dependencies of the packages in the passed-in paths.
+struct DepsCommand {
+    #[clap(subcommand)]
+    command: DepsSubcommand,
+}
+
+#[derive(Subcommand)]
+enum DepsSubcommand {
+    Unused(DepsUnusedCommand),
+}
+
+#[derive(Parser)]
+#[clap(rename_all = "camel_case")]
+/// List the `dependencies` of package.json files within the passed-in paths that no source file imports.
+struct DepsUnusedCommand {
+    /// Paths can be either directories or files.
+    paths: Vec<String>,
+
+    #[command(flatten)]
+    lint_args: LintArgs,
+}
+
 fn main() -> Result<(), Box<dyn Error>> {
     let opt = Opt::parse();
 
@@ -68,28 +142,97 @@ fn main() -> Result<(), Box<dyn Error>> {
         Commands::Diagram(command) => run_diagram_command(command),
         Commands::Fix(command) => run_fix_command(command),
         Commands::Format(command) => run_format_command(command),
+        Commands::Deps(command) => match command.command {
+            DepsSubcommand::Unused(command) => run_deps_unused_command(command),
+        },
     }
 }
 
-fn run_lint_command(command: LintCommand) -> Result<(), Box<dyn Error>> {
-    let Ok(sample_path) = fs::canonicalize(Path::new(&command.paths[0])) else {
-        return Err(format!("Target path '{}' does not exist.", &command.paths[0]).into());
+/// Returns the canonicalized target path and the root of the package that
+/// contains it.
+fn find_target_and_root(path: &str) -> Result<(PathBuf, PathBuf), Box<dyn Error>> {
+    let Ok(target) = fs::canonicalize(Path::new(path)) else {
+        return Err(format!("Target path '{}' does not exist.", path).into());
     };
-    let root = find_package_json_directory(&sample_path)
-        .ok_or("No package.json found in any parent directory.")?;
+    let root = find_package_json_directory(&target).ok_or_else(|| {
+        format!(
+            "No package.json found in any parent directory of '{}'.",
+            path
+        )
+    })?;
+    Ok((target, root))
+}
 
-    let mut all_violations: HashSet<Violation> = HashSet::new();
-    for path in command.paths.iter() {
-        let Ok(target) = fs::canonicalize(Path::new(path)) else {
-            return Err(format!("Target path '{}' does not exist.", path).into());
-        };
-        let violations = list_violations(&root, &target, false)?;
-        all_violations.extend(violations);
+/// Lists the violations of each passed-in path, keeping the violations of
+/// each package apart since their paths are relative to its root.
+fn group_violations_by_root<F>(
+    paths: &[String],
+    mut list_violations: F,
+) -> Result<BTreeMap<PathBuf, HashSet<Violation>>, Box<dyn Error>>
+where
+    F: FnMut(&Path, &Path) -> Result<Vec<Violation>, Box<dyn Error>>,
+{
+    let mut violations_by_root: BTreeMap<PathBuf, HashSet<Violation>> = BTreeMap::new();
+    for path in paths {
+        let (target, root) = find_target_and_root(path)?;
+        let violations = list_violations(&root, &target)?;
+        violations_by_root
+            .entry(root)
+            .or_default()
+            .extend(violations);
     }
+    Ok(violations_by_root)
+}
+
+/// Returns the violations of each package that has any, along with a label
+/// naming the package when there are several.
+fn label_violations_by_root(
+    violations_by_root: BTreeMap<PathBuf, HashSet<Violation>>,
+) -> Vec<(Option<String>, HashSet<Violation>)> {
+    let label_packages = violations_by_root
+        .values()
+        .filter(|violations| !violations.is_empty())
+        .count()
+        > 1;
+    violations_by_root
+        .into_iter()
+        .filter(|(_, violations)| !violations.is_empty())
+        .map(|(root, violations)| {
+            let label = label_packages.then(|| format!("package {}", root.display()));
+            (label, violations)
+        })
+        .collect()
+}
 
-    if !all_violations.is_empty() {
-        let count = all_violations.len();
-        pretty_print_violations(all_violations);
+/// Prints the violations of each package, labelled by package when there
+/// are several, and returns their count.
+fn print_violations_by_root(violations_by_root: BTreeMap<PathBuf, HashSet<Violation>>) -> usize {
+    let mut count = 0;
+    for (label, violations) in label_violations_by_root(violations_by_root) {
+        if let Some(label) = label {
+            println!("{}", label);
+            println!();
+        }
+        count += violations.len();
+        pretty_print_violations(violations);
+    }
+    count
+}
+
+fn run_lint_command(command: LintCommand) -> Result<(), Box<dyn Error>> {
+    let options = LintOptions {
+        require_suppression_reasons: command.require_suppression_reasons,
+        report_unresolved_imports: command.report_unresolved_imports,
+        report_undeclared_dependencies: command.report_undeclared_dependencies,
+        report_unused_dependencies: command.report_unused_dependencies,
+        ..command.lint_args.to_options()
+    };
+
+    let violations_by_root = group_violations_by_root(&command.paths, |root, target| {
+        list_violations(root, target, &options, false)
+    })?;
+    let count = print_violations_by_root(violations_by_root);
+    if count > 0 {
         return Err(format!("{} violations.", count).into());
     }
 
@@ -115,20 +258,14 @@ fn run_diagram_command(command: DiagramCommand) -> Result<(), Box<dyn Error>> {
 }
 
 fn run_fix_command(command: FixCommand) -> Result<(), Box<dyn Error>> {
-    let Ok(sample_path) = fs::canonicalize(Path::new(&command.paths[0])) else {
-        return Err(format!("Target path '{}' does not exist.", &command.paths[0]).into());
-    };
-    let root = find_package_json_directory(&sample_path)
-        .ok_or("No package.json found in any parent directory.")?;
+    let options = command.lint_args.to_options();
 
     let mut i = 0;
     for path in command.paths.iter() {
+        let (target, root) = find_target_and_root(path)?;
         loop {
-            let Ok(target) = fs::canonicalize(Path::new(path)) else {
-                return Err(format!("Target path '{}' does not exist.", path).into());
-            };
-            let violations = list_violations(&root, &target, true)?;
-            if violations.is_empty() {
+            let violations = list_violations(&root, &target, &options, true)?;
+            if !violations.iter().any(Violation::is_fixable) {
                 break;
             }
             for violation in violations {
@@ -139,6 +276,18 @@ fn run_fix_command(command: FixCommand) -> Result<(), Box<dyn Error>> {
                     Violation::ReferenceToNonexistentDirectory(issue) => {
                         ts_deplint::remove_reference_to_nonexistent_directory(&root, &issue)?;
                     }
+                    Violation::SuppressionWithoutReason(_)
+                    | Violation::UnresolvedImport(_)
+                    | Violation::ImportEscapesRoot(_)
+                    | Violation::DisallowedPackageViolation(_)
+                    | Violation::DisallowedBuiltinViolation(_)
+                    | Violation::UndeclaredDependency(_)
+                    | Violation::UnusedDependency(_)
+                    | Violation::NonExportedImport(_)
+                    | Violation::VisibilityViolation(_) => {}
+                    Violation::UnusedSuppression(issue) => {
+                        ts_deplint::remove_unused_suppression(&root, &issue)?;
+                    }
                 }
             }
             i += 1;
@@ -165,3 +314,76 @@ fn run_format_command(command: FormatCommand) -> Result<(), Box<dyn Error>> {
 
     Ok(())
 }
+
+fn run_deps_unused_command(command: DepsUnusedCommand) -> Result<(), Box<dyn Error>> {
+    let options = command.lint_args.to_options();
+
+    let unused_by_root = group_violations_by_root(&command.paths, |root, target| {
+        let unused = list_unused_dependencies(root, target, &options)?;
+        Ok(unused
+            .into_iter()
+            .map(Violation::UnusedDependency)
+            .collect())
+    })?;
+    let count = print_violations_by_root(unused_by_root);
+    if count > 0 {
+        return Err(format!("{} unused dependencies.", count).into());
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_group_and_label_violations_by_root() {
+    let temp_dir = test_utils::TempDir::new("roots");
+    for package in ["billing", "ledger", "reports"] {
+        temp_dir.create_dirs(&[&format!("{}/src", package)]);
+        temp_dir.write(&format!("{}/package.json", package), "{}");
+    }
+    let dir = fs::canonicalize(&temp_dir).unwrap();
+    let paths = ["billing/src", "billing", "ledger/src", "reports"]
+        .map(|path| dir.join(path).to_str().unwrap().to_string());
+
+    // Every package reports a violation of the same relative path, except
+    // for `reports`, which has none.
+    let group = |paths: &[String]| {
+        group_violations_by_root(paths, |root, _target| {
+            if root.ends_with("reports") {
+                return Ok(vec![]);
+            }
+            Ok(vec![Violation::UnusedDependency(
+                ts_deplint::UnusedDependency {
+                    file_path: "package.json".to_string(),
+                    package: "lodash".to_string(),
+                },
+            )])
+        })
+        .unwrap()
+    };
+    assert_eq!(
+        group(&paths)
+            .iter()
+            .map(|(root, violations)| (root.clone(), violations.len()))
+            .collect::<Vec<_>>(),
+        vec![
+            (dir.join("billing"), 1),
+            (dir.join("ledger"), 1),
+            (dir.join("reports"), 0),
+        ]
+    );
+
+    let labels = |violations_by_root| {
+        label_violations_by_root(violations_by_root)
+            .into_iter()
+            .map(|(label, _)| label)
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        labels(group(&paths)),
+        vec![
+            Some(format!("package {}", dir.join("billing").display())),
+            Some(format!("package {}", dir.join("ledger").display())),
+        ]
+    );
+    assert_eq!(labels(group(&paths[..2])), vec![None]);
+}
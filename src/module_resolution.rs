@@ -0,0 +1,98 @@
+use std::path::{Path, PathBuf};
+
+use crate::package_json::{self, PACKAGE_JSON_FILE_NAME};
+
+/// Extensions tried, in order, for specifiers without one, as with
+/// TypeScript's `bundler` module resolution.
+const MODULE_EXTENSIONS: &[&str] = &[
+    "ts", "tsx", "d.ts", "mts", "d.mts", "cts", "d.cts", "js", "jsx", "mjs", "cjs",
+];
+
+/// TypeScript sources that a JavaScript extension may refer to, as with
+/// `node16` module resolution, where imports name the emitted file.
+const JS_EXTENSION_SUBSTITUTIONS: &[(&str, &[&str])] = &[
+    ("js", &["ts", "tsx", "d.ts"]),
+    ("jsx", &["tsx", "d.ts"]),
+    ("mjs", &["mts", "d.mts"]),
+    ("cjs", &["cts", "d.cts"]),
+];
+
+/// Returns the file that the passed-in absolute module path refers to,
+/// trying the path itself, the path with each module extension, and the
+/// entry point of the directory at the path, in that order.
+pub fn resolve_module_path(path: &Path) -> Option<PathBuf> {
+    resolve_as_file(path).or_else(|| resolve_as_directory(path))
+}
+
+fn resolve_as_file(path: &Path) -> Option<PathBuf> {
+    let extension = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+    let substitutions = JS_EXTENSION_SUBSTITUTIONS
+        .iter()
+        .find(|(js_extension, _)| *js_extension == extension)
+        .map_or(&[][..], |(_, ts_extensions)| *ts_extensions);
+    substitutions
+        .iter()
+        .map(|ts_extension| path.with_extension(ts_extension))
+        .chain([path.to_path_buf()])
+        .chain(
+            MODULE_EXTENSIONS
+                .iter()
+                .map(|extension| with_extension_appended(path, extension)),
+        )
+        .find(|candidate| candidate.is_file())
+}
+
+/// Resolves a directory to the `types`, `typings` or `main` entry point of
+/// its package.json, or otherwise to its index file.
+fn resolve_as_directory(path: &Path) -> Option<PathBuf> {
+    if !path.is_dir() {
+        return None;
+    }
+    if path.join(PACKAGE_JSON_FILE_NAME).is_file() {
+        if let Ok(package_json) = package_json::read_package_json(path) {
+            let entry_points = [package_json.types, package_json.typings, package_json.main];
+            for entry_point in entry_points.into_iter().flatten() {
+                let entry_point = path.join(entry_point);
+                let resolved = resolve_as_file(&entry_point)
+                    .or_else(|| resolve_as_file(&entry_point.join("index")));
+                if resolved.is_some() {
+                    return resolved;
+                }
+            }
+        }
+    }
+    resolve_as_file(&path.join("index"))
+}
+
+fn with_extension_appended(path: &Path, extension: &str) -> PathBuf {
+    let mut path = path.as_os_str().to_owned();
+    path.push(".");
+    path.push(extension);
+    PathBuf::from(path)
+}
+
+#[test]
+fn test_resolve_module_path() {
+    let dir = crate::test_utils::TempDir::new("resolve");
+    for file in [
+        "util.ts",
+        "view.tsx",
+        "legacy.js",
+        "styles.css",
+        "widgets/index.ts",
+        "ledger/src/main.ts",
+    ] {
+        dir.write(file, "");
+    }
+    dir.write("ledger/package.json", r#"{"main": "./src/main.js"}"#);
+
+    let resolve = |specifier: &str| resolve_module_path(&dir.join(specifier));
+    assert_eq!(resolve("util"), Some(dir.join("util.ts")));
+    assert_eq!(resolve("util.js"), Some(dir.join("util.ts")));
+    assert_eq!(resolve("view.js"), Some(dir.join("view.tsx")));
+    assert_eq!(resolve("legacy.js"), Some(dir.join("legacy.js")));
+    assert_eq!(resolve("styles.css"), Some(dir.join("styles.css")));
+    assert_eq!(resolve("widgets"), Some(dir.join("widgets/index.ts")));
+    assert_eq!(resolve("ledger"), Some(dir.join("ledger/src/main.ts")));
+    assert_eq!(resolve("missing"), None);
+}
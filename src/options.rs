@@ -0,0 +1,75 @@
+/// Extensions of TypeScript and JavaScript modules that are linted by
+/// default.
+pub const DEFAULT_EXTENSIONS: &[&str] = &["ts", "tsx", "mts", "cts", "js", "jsx", "mjs", "cjs"];
+
+/// Options controlling which files are linted.
+#[derive(Clone)]
+pub struct LintOptions {
+    /// Extensions of the source files to lint, without the leading dot.
+    pub extensions: Vec<String>,
+    /// Whether to lint declaration files like `foo.d.ts`.
+    pub include_declaration_files: bool,
+    /// Whether suppression comments must explain themselves with a
+    /// `-- reason`. Suppressions without one are reported and don't apply.
+    pub require_suppression_reasons: bool,
+    /// Whether to report relative and root-relative imports that don't
+    /// resolve to any file.
+    pub report_unresolved_imports: bool,
+    /// Whether to report imports of packages that the nearest package.json
+    /// doesn't declare. devDependencies may only be imported by tests.
+    pub report_undeclared_dependencies: bool,
+    /// Whether to report `dependencies` of package.json files within the
+    /// target that no linted file imports.
+    pub report_unused_dependencies: bool,
+}
+
+impl Default for LintOptions {
+    fn default() -> Self {
+        Self {
+            extensions: DEFAULT_EXTENSIONS.iter().map(|e| e.to_string()).collect(),
+            include_declaration_files: false,
+            require_suppression_reasons: false,
+            report_unresolved_imports: false,
+            report_undeclared_dependencies: false,
+            report_unused_dependencies: false,
+        }
+    }
+}
+
+impl LintOptions {
+    /// Returns whether the file with the passed-in name should be linted.
+    pub fn is_source_file(&self, file_name: &str) -> bool {
+        let Some((stem, extension)) = file_name.rsplit_once('.') else {
+            return false;
+        };
+        if !self
+            .extensions
+            .iter()
+            .any(|e| e.trim_start_matches('.') == extension)
+        {
+            return false;
+        }
+        self.include_declaration_files || !stem.ends_with(".d")
+    }
+}
+
+#[test]
+fn test_is_source_file() {
+    let options = LintOptions::default();
+    assert!(options.is_source_file("foo.ts"));
+    assert!(options.is_source_file("Foo.tsx"));
+    assert!(options.is_source_file("foo.test.mjs"));
+    assert!(!options.is_source_file("foo.d.ts"));
+    assert!(!options.is_source_file("foo.d.mts"));
+    assert!(!options.is_source_file("foo.json"));
+    assert!(!options.is_source_file("ts"));
+
+    let options = LintOptions {
+        extensions: vec![".ts".to_string()],
+        include_declaration_files: true,
+        ..LintOptions::default()
+    };
+    assert!(options.is_source_file("foo.ts"));
+    assert!(options.is_source_file("foo.d.ts"));
+    assert!(!options.is_source_file("foo.tsx"));
+}
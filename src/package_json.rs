@@ -0,0 +1,143 @@
+use std::{collections::BTreeMap, error::Error, fs, path::Path};
+
+use serde::Deserialize;
+use serde_json::Value;
+
+pub const PACKAGE_JSON_FILE_NAME: &str = "package.json";
+
+/// Conditions of `imports` and `exports` targets that apply when resolving
+/// TypeScript sources. Targets are picked in the order they are declared.
+const ACTIVE_CONDITIONS: &[&str] = &["types", "import", "module", "node", "require", "default"];
+
+/// The fields of a package.json file relevant to module resolution and
+/// dependency checks.
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct PackageJson {
+    pub name: Option<String>,
+    pub workspaces: Option<Workspaces>,
+    pub types: Option<String>,
+    pub typings: Option<String>,
+    pub main: Option<String>,
+    /// Subpath imports, like `#billing/*`, mapped to their targets.
+    #[serde(default)]
+    pub imports: BTreeMap<String, Value>,
+    /// Entry points of the package, in any of the forms of `exports`.
+    pub exports: Option<Value>,
+    #[serde(default)]
+    pub dependencies: BTreeMap<String, String>,
+    #[serde(default)]
+    pub dev_dependencies: BTreeMap<String, String>,
+    #[serde(default)]
+    pub peer_dependencies: BTreeMap<String, String>,
+    #[serde(default)]
+    pub optional_dependencies: BTreeMap<String, String>,
+}
+
+/// The `workspaces` field, either a list of package directory globs or, as
+/// in yarn, an object with a `packages` list.
+#[derive(Deserialize)]
+#[serde(untagged)]
+pub enum Workspaces {
+    Patterns(Vec<String>),
+    Object {
+        #[serde(default)]
+        packages: Vec<String>,
+    },
+}
+
+impl PackageJson {
+    /// Returns the target of the passed-in `#` specifier according to the
+    /// `imports` field. Targets are either relative to the package.json
+    /// directory, like `./src/billing/ledger.ts`, or bare package names.
+    pub fn resolve_subpath_import(&self, specifier: &str) -> Option<String> {
+        let (target, wildcard) = match_subpath_pattern(&self.imports, specifier)?;
+        let target = select_target(target)?;
+        Some(target.replace('*', wildcard))
+    }
+}
+
+pub fn read_package_json(dir: &Path) -> Result<PackageJson, Box<dyn Error>> {
+    let path = dir.join(PACKAGE_JSON_FILE_NAME);
+    let content =
+        fs::read_to_string(&path).map_err(|e| format!("Failed to read {:?}: {}", path, e))?;
+    let package_json =
+        serde_json::from_str(&content).map_err(|e| format!("Failed to parse {:?}: {}", path, e))?;
+    Ok(package_json)
+}
+
+/// Returns the value of the key of an `imports` or `exports` map matching
+/// the passed-in subpath, along with the part matched by the key's `*`.
+/// Exact keys win over patterns, and longer patterns over shorter ones.
+pub fn match_subpath_pattern<'m, 's>(
+    map: &'m BTreeMap<String, Value>,
+    subpath: &'s str,
+) -> Option<(&'m Value, &'s str)> {
+    if let Some(value) = map.get(subpath) {
+        return Some((value, ""));
+    }
+    let mut best_match: Option<(usize, &Value, &str)> = None;
+    for (pattern, value) in map {
+        let Some((prefix, suffix)) = pattern.split_once('*') else {
+            continue;
+        };
+        if subpath.len() < prefix.len() + suffix.len()
+            || !subpath.starts_with(prefix)
+            || !subpath.ends_with(suffix)
+        {
+            continue;
+        }
+        if best_match.is_none_or(|(best_len, _, _)| prefix.len() > best_len) {
+            let wildcard = &subpath[prefix.len()..subpath.len() - suffix.len()];
+            best_match = Some((prefix.len(), value, wildcard));
+        }
+    }
+    best_match.map(|(_, value, wildcard)| (value, wildcard))
+}
+
+/// Returns the first target of an `imports` or `exports` value that applies,
+/// descending into condition objects and fallback arrays. A `null` target
+/// excludes the subpath.
+pub fn select_target(value: &Value) -> Option<&str> {
+    match value {
+        Value::String(target) => Some(target),
+        Value::Array(fallbacks) => fallbacks.iter().find_map(select_target),
+        Value::Object(conditions) => conditions
+            .iter()
+            .filter(|(condition, _)| ACTIVE_CONDITIONS.contains(&condition.as_str()))
+            .find_map(|(_, value)| select_target(value)),
+        _ => None,
+    }
+}
+
+#[test]
+fn test_resolve_subpath_import() {
+    let package_json: PackageJson = serde_json::from_str(
+        r##"{
+            "imports": {
+                "#billing/*": "./src/billing/*.ts",
+                "#billing/internal/*": null,
+                "#config": { "browser": "./src/config.browser.ts", "default": "./src/config.ts" },
+                "#dep": "lodash"
+            }
+        }"##,
+    )
+    .unwrap();
+    assert_eq!(
+        package_json.resolve_subpath_import("#billing/ledger"),
+        Some("./src/billing/ledger.ts".to_string())
+    );
+    assert_eq!(
+        package_json.resolve_subpath_import("#billing/internal/x"),
+        None
+    );
+    assert_eq!(
+        package_json.resolve_subpath_import("#config"),
+        Some("./src/config.ts".to_string())
+    );
+    assert_eq!(
+        package_json.resolve_subpath_import("#dep"),
+        Some("lodash".to_string())
+    );
+    assert_eq!(package_json.resolve_subpath_import("#missing"), None);
+}
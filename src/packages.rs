@@ -0,0 +1,76 @@
+use serde::{Deserialize, Serialize};
+
+use crate::glob::glob_match;
+
+/// Third-party packages that code in a directory and its descendants may
+/// import. Patterns may use globs, like `@aws-sdk/*`.
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct PackageRules {
+    /// When not empty, only packages matching one of these may be imported.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allow: Vec<String>,
+    /// Packages that may not be imported, even if allowed.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub deny: Vec<String>,
+}
+
+impl PackageRules {
+    pub fn allows(&self, package: &str) -> bool {
+        let is_allowed = self.allow.is_empty()
+            || self
+                .allow
+                .iter()
+                .any(|pattern| glob_match(pattern, package));
+        is_allowed && !self.deny.iter().any(|pattern| glob_match(pattern, package))
+    }
+}
+
+/// Returns the name of the package that the passed-in bare specifier
+/// imports, like `@aws-sdk/client-s3` for `@aws-sdk/client-s3/commands`.
+pub fn package_name(specifier: &str) -> Option<&str> {
+    if specifier.is_empty() || specifier.starts_with(['.', '/', '#']) {
+        return None;
+    }
+    let segments = if specifier.starts_with('@') { 2 } else { 1 };
+    let end = specifier
+        .match_indices('/')
+        .nth(segments - 1)
+        .map_or(specifier.len(), |(index, _)| index);
+    Some(&specifier[..end])
+}
+
+#[test]
+fn test_package_name() {
+    assert_eq!(package_name("react"), Some("react"));
+    assert_eq!(package_name("react-dom/client"), Some("react-dom"));
+    assert_eq!(
+        package_name("@aws-sdk/client-s3"),
+        Some("@aws-sdk/client-s3")
+    );
+    assert_eq!(
+        package_name("@aws-sdk/client-s3/commands"),
+        Some("@aws-sdk/client-s3")
+    );
+    assert_eq!(package_name("node:fs"), Some("node:fs"));
+    assert_eq!(package_name("./react"), None);
+    assert_eq!(package_name("#config"), None);
+}
+
+#[test]
+fn test_package_rules_allows() {
+    let rules = PackageRules {
+        allow: vec![],
+        deny: vec!["axios".to_string(), "@aws-sdk/*".to_string()],
+    };
+    assert!(rules.allows("zod"));
+    assert!(!rules.allows("axios"));
+    assert!(!rules.allows("@aws-sdk/client-s3"));
+
+    let rules = PackageRules {
+        allow: vec!["react".to_string(), "react-*".to_string()],
+        deny: vec!["react-query".to_string()],
+    };
+    assert!(rules.allows("react-dom"));
+    assert!(!rules.allows("react-query"));
+    assert!(!rules.allows("axios"));
+}
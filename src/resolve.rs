@@ -0,0 +1,426 @@
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    error::Error,
+    fs::canonicalize,
+    path::{Component, Path, PathBuf},
+    rc::Rc,
+};
+
+use crate::{
+    environment, module_resolution,
+    package_json::{self, PackageJson},
+    root::find_package_json_directory,
+    tsconfig::{self, TsConfig},
+    workspace::{self, PackageImport, Workspace},
+};
+
+/// A package.json file along with the directory containing it.
+type PackageJsonInDirectory = (PathBuf, Rc<PackageJson>);
+
+/// The module that an import specifier refers to.
+#[derive(Debug, PartialEq)]
+pub enum ResolvedImport {
+    /// A file, relative to the root directory.
+    File(PathBuf),
+    /// A path relative to the root directory that doesn't resolve to a file,
+    /// but whose parent directory exists.
+    Unresolved(PathBuf),
+    /// An absolute path outside of the root directory that a relative or
+    /// aliased import escapes to.
+    OutsideRoot(PathBuf),
+    /// A third-party package, or a workspace package outside of the root
+    /// directory.
+    External,
+    /// A subpath of a workspace package that its `exports` don't include.
+    NotExported { package: String },
+}
+
+impl ResolvedImport {
+    /// Returns the path relative to the root directory that directory rules
+    /// apply to, if any.
+    pub fn path(&self) -> Option<&Path> {
+        match self {
+            ResolvedImport::File(path) | ResolvedImport::Unresolved(path) => Some(path),
+            ResolvedImport::OutsideRoot(_)
+            | ResolvedImport::External
+            | ResolvedImport::NotExported { .. } => None,
+        }
+    }
+}
+
+/// Maps import specifiers to the modules they refer to.
+pub struct ImportResolver {
+    root: PathBuf,
+    workspace: Option<Workspace>,
+    /// The tsconfig.json nearest to each importing directory, if any.
+    tsconfigs_by_directory: RefCell<HashMap<PathBuf, Option<Rc<TsConfig>>>>,
+    /// The tsconfig.json files read so far, by path.
+    tsconfigs_by_path: RefCell<HashMap<PathBuf, Option<Rc<TsConfig>>>>,
+    /// The package.json files that subpath imports were resolved through, by
+    /// directory.
+    package_jsons: RefCell<HashMap<PathBuf, Rc<PackageJson>>>,
+}
+
+impl ImportResolver {
+    /// Creates a resolver using the packages of the workspace enclosing the
+    /// root directory, if any.
+    pub fn new(root: &Path) -> Result<Self, Box<dyn Error>> {
+        Ok(Self {
+            root: root.to_path_buf(),
+            tsconfigs_by_directory: RefCell::default(),
+            tsconfigs_by_path: RefCell::default(),
+            workspace: workspace::find_workspace(root)?,
+            package_jsons: RefCell::default(),
+        })
+    }
+
+    /// Resolves the passed-in import specifier of a file in the current
+    /// directory to the module it refers to.
+    pub fn resolve_import(
+        &self,
+        import: &str,
+        current_directory: &Path,
+    ) -> Result<ResolvedImport, Box<dyn Error>> {
+        if import.trim().is_empty() {
+            // Edge case handling. An empty string would lead to us
+            // resolving the root directory.
+            return Ok(ResolvedImport::External);
+        }
+
+        let import_path = Path::new(&import);
+
+        // Paths that tsconfig `paths` aliases map the import to, which are
+        // only tried as files since aliases like `"*": ["src/*"]` match
+        // third-party packages too.
+        let mut aliased = vec![];
+        let candidates: Vec<PathBuf> = if import.starts_with("#") {
+            // Subpath imports are mapped by the `imports` field of the nearest
+            // package.json, either to a file of its package or to another
+            // package.
+            let Some((package_directory, package_json)) =
+                self.nearest_package_json(current_directory)?
+            else {
+                return Ok(ResolvedImport::External);
+            };
+            match package_json.resolve_subpath_import(import) {
+                Some(target) if target.starts_with("./") => vec![package_directory.join(target)],
+                Some(target) if !target.starts_with("#") => {
+                    return self.resolve_import(&target, current_directory);
+                }
+                _ => vec![],
+            }
+        } else if import.starts_with(".") {
+            // Relative imports are relative to the current directory of the file.
+            vec![current_directory.join(import_path)]
+        } else if environment::node_builtin(import).is_some() {
+            return Ok(ResolvedImport::External);
+        } else {
+            // Non-relative imports may be aliased by tsconfig `paths`, name a
+            // workspace package, be relative to the tsconfig `baseUrl`, or be
+            // relative to the root directory of the project, in that order.
+            let mut candidates = vec![];
+            let tsconfig = self.nearest_tsconfig(current_directory);
+            if let Some(tsconfig) = &tsconfig {
+                aliased = tsconfig.resolve_paths_alias(import);
+            }
+            if let Some(workspace) = &self.workspace {
+                match workspace.resolve_package_import(import) {
+                    Some(PackageImport::NotExported { package }) => {
+                        return Ok(ResolvedImport::NotExported { package });
+                    }
+                    Some(PackageImport::Paths(package_paths)) => {
+                        if !package_paths
+                            .iter()
+                            .all(|path| path.starts_with(&self.root))
+                        {
+                            // Directory rules only apply within the root, so
+                            // treat packages outside of it as third-party.
+                            return Ok(ResolvedImport::External);
+                        }
+                        candidates.extend(package_paths);
+                    }
+                    None => {}
+                }
+            }
+            let base_url = tsconfig.as_ref().and_then(|t| t.base_url.as_ref());
+            // Only consider specifiers like `src/billing` relative to a
+            // directory when their first segment exists there, so that
+            // third-party packages like `lodash` aren't mistaken for them.
+            let first_segment = import_path.components().next().map(|c| c.as_os_str());
+            for base in base_url.into_iter().chain([&self.root]) {
+                if first_segment.is_some_and(|segment| base.join(segment).exists()) {
+                    candidates.push(base.join(import_path));
+                }
+            }
+            candidates
+        };
+
+        for candidate in aliased.iter().chain(&candidates) {
+            if let Some(file) = module_resolution::resolve_module_path(candidate) {
+                return Ok(self.relative_to_root(canonicalize(file)?, ResolvedImport::File));
+            }
+        }
+
+        if !import.starts_with(".") && self.is_installed_package(import) {
+            // Directories of the root may share their name with installed
+            // packages.
+            return Ok(ResolvedImport::External);
+        }
+
+        for fully_qualified_path in &candidates {
+            let file_name = fully_qualified_path.file_name().unwrap_or_default();
+            // We expect there to always be a parent directory since we
+            // append the import path to a directory.
+            let directory_path = fully_qualified_path
+                .parent()
+                .expect("Unable to get parent directory");
+
+            let Ok(canonicalized_directory_path) = canonicalize(directory_path) else {
+                continue;
+            };
+
+            let path = canonicalized_directory_path.join(file_name);
+            return Ok(self.relative_to_root(path, ResolvedImport::Unresolved));
+        }
+
+        if import.starts_with(".") {
+            // Relative imports of missing directories can't be third-party.
+            if let Some(candidate) = candidates.first() {
+                let path = normalize_path(candidate);
+                return Ok(self.relative_to_root(path, ResolvedImport::Unresolved));
+            }
+        }
+
+        // If the path doesn't exist, we assume it's an import from a third-party library.
+        Ok(ResolvedImport::External)
+    }
+
+    /// Returns whether the passed-in package name names a package of the
+    /// enclosing workspace.
+    pub fn is_workspace_package(&self, package: &str) -> bool {
+        self.workspace
+            .as_ref()
+            .is_some_and(|workspace| workspace.packages.contains_key(package))
+    }
+
+    /// Returns the tsconfig.json nearest to the passed-in directory within
+    /// the root directory, which TypeScript resolves the directory's files
+    /// with. Imports are resolved as if there were none when it can't be
+    /// read, so that a broken tsconfig.json doesn't stop linting.
+    fn nearest_tsconfig(&self, directory: &Path) -> Option<Rc<TsConfig>> {
+        if let Some(tsconfig) = self.tsconfigs_by_directory.borrow().get(directory) {
+            return tsconfig.clone();
+        }
+        let tsconfig = tsconfig::find_tsconfig(directory, &self.root).and_then(|path| {
+            let cached = self.tsconfigs_by_path.borrow().get(&path).cloned();
+            cached.unwrap_or_else(|| {
+                let tsconfig = tsconfig::read_tsconfig(&path).ok().map(Rc::new);
+                self.tsconfigs_by_path
+                    .borrow_mut()
+                    .insert(path, tsconfig.clone());
+                tsconfig
+            })
+        });
+        self.tsconfigs_by_directory
+            .borrow_mut()
+            .insert(directory.to_path_buf(), tsconfig.clone());
+        tsconfig
+    }
+
+    /// Returns the directory of the package.json nearest to the passed-in
+    /// directory, along with its contents.
+    fn nearest_package_json(
+        &self,
+        directory: &Path,
+    ) -> Result<Option<PackageJsonInDirectory>, Box<dyn Error>> {
+        let Some(package_directory) = find_package_json_directory(directory) else {
+            return Ok(None);
+        };
+        if let Some(package_json) = self.package_jsons.borrow().get(&package_directory) {
+            return Ok(Some((package_directory, package_json.clone())));
+        }
+        let package_json = Rc::new(package_json::read_package_json(&package_directory)?);
+        self.package_jsons
+            .borrow_mut()
+            .insert(package_directory.clone(), package_json.clone());
+        Ok(Some((package_directory, package_json)))
+    }
+
+    /// Wraps the passed-in absolute path, made relative to the root
+    /// directory, or reports that it escapes the root directory.
+    fn relative_to_root(
+        &self,
+        path: PathBuf,
+        within_root: fn(PathBuf) -> ResolvedImport,
+    ) -> ResolvedImport {
+        match path.strip_prefix(&self.root) {
+            Ok(path_from_root) => within_root(path_from_root.to_path_buf()),
+            Err(_) => ResolvedImport::OutsideRoot(path),
+        }
+    }
+
+    /// Returns whether the package named by the passed-in bare specifier is
+    /// installed in a `node_modules` directory of the root or its ancestors.
+    fn is_installed_package(&self, specifier: &str) -> bool {
+        let segments = if specifier.starts_with('@') { 2 } else { 1 };
+        let package_name = specifier
+            .split('/')
+            .take(segments)
+            .collect::<Vec<_>>()
+            .join("/");
+        self.root
+            .ancestors()
+            .any(|dir| dir.join("node_modules").join(&package_name).exists())
+    }
+}
+
+/// Resolves `.` and `..` components of the passed-in absolute path without
+/// accessing the file system.
+fn normalize_path(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            component => normalized.push(component),
+        }
+    }
+    normalized
+}
+
+#[test]
+fn test_normalize_path() {
+    assert_eq!(
+        normalize_path(Path::new("/repo/src/billing/../ledger/./entries")),
+        PathBuf::from("/repo/src/ledger/entries")
+    );
+}
+
+#[test]
+fn test_resolve_subpath_import_of_nested_package() {
+    let dir = crate::test_utils::TempDir::new("subpath");
+    dir.create_dirs(&["packages/p/src/a"]);
+    dir.write("package.json", "{}");
+    dir.write(
+        "packages/p/package.json",
+        r##"{"imports": {"#b/*": "./src/b/*.ts"}}"##,
+    );
+    dir.write("packages/p/src/b/y.ts", "");
+    dir.write("packages/q/tsconfig.json", "{ not json");
+    let root = canonicalize(&dir).unwrap();
+
+    let resolver = ImportResolver::new(&root).unwrap();
+    // Broken tsconfig.json files are ignored.
+    assert_eq!(
+        resolver
+            .resolve_import("packages/p/src/b/y", &root.join("packages/q"))
+            .unwrap(),
+        ResolvedImport::File(PathBuf::from("packages/p/src/b/y.ts"))
+    );
+    assert_eq!(
+        resolver
+            .resolve_import("#b/y", &root.join("packages/p/src/a"))
+            .unwrap(),
+        ResolvedImport::File(PathBuf::from("packages/p/src/b/y.ts"))
+    );
+    assert_eq!(
+        resolver.resolve_import("#b/y", &root).unwrap(),
+        ResolvedImport::External
+    );
+}
+
+#[test]
+fn test_resolve_import_with_nearest_tsconfig() {
+    let dir = crate::test_utils::TempDir::new("nearest");
+    dir.create_dirs(&["packages/p/src/a"]);
+    dir.write("package.json", "{}");
+    dir.write(
+        "packages/p/tsconfig.json",
+        r#"{"compilerOptions": {"paths": {"@b/*": ["./src/b/*"]}}}"#,
+    );
+    dir.write("packages/p/src/b/y.ts", "");
+    let root = canonicalize(&dir).unwrap();
+
+    let resolver = ImportResolver::new(&root).unwrap();
+    assert_eq!(
+        resolver
+            .resolve_import("@b/y", &root.join("packages/p/src/a"))
+            .unwrap(),
+        ResolvedImport::File(PathBuf::from("packages/p/src/b/y.ts"))
+    );
+    assert_eq!(
+        resolver.resolve_import("@b/y", &root).unwrap(),
+        ResolvedImport::External
+    );
+}
+
+#[test]
+fn test_resolve_import_outside_root() {
+    let temp_dir = crate::test_utils::TempDir::new("outside");
+    temp_dir.create_dirs(&["pkg/src"]);
+    temp_dir.write("pkg/package.json", "{}");
+    temp_dir.write("shared/x.ts", "");
+    let dir = canonicalize(&temp_dir).unwrap();
+    let root = dir.join("pkg");
+
+    let resolver = ImportResolver::new(&root).unwrap();
+    assert_eq!(
+        resolver.relative_to_root(root.join("src/a.ts"), ResolvedImport::File),
+        ResolvedImport::File(PathBuf::from("src/a.ts"))
+    );
+    assert_eq!(
+        resolver.relative_to_root(dir.join("shared/x.ts"), ResolvedImport::File),
+        ResolvedImport::OutsideRoot(dir.join("shared/x.ts"))
+    );
+
+    let src = root.join("src");
+    let resolve = |specifier: &str| resolver.resolve_import(specifier, &src).unwrap();
+    assert_eq!(
+        resolve("../../shared/x"),
+        ResolvedImport::OutsideRoot(dir.join("shared/x.ts"))
+    );
+    assert_eq!(
+        resolve("../../shared/y"),
+        ResolvedImport::OutsideRoot(dir.join("shared/y"))
+    );
+    // Paths of missing directories are normalized without the file system.
+    assert_eq!(
+        resolve("../../missing/./z/../y"),
+        ResolvedImport::OutsideRoot(dir.join("missing/y"))
+    );
+    assert_eq!(
+        resolve("./missing/y"),
+        ResolvedImport::Unresolved(PathBuf::from("src/missing/y"))
+    );
+    assert_eq!(resolve("../../shared/x").path(), None);
+}
+
+#[test]
+fn test_resolve_import_with_catch_all_alias() {
+    let dir = crate::test_utils::TempDir::new("catch_all");
+    dir.write("package.json", "{}");
+    dir.write(
+        "tsconfig.json",
+        r#"{"compilerOptions": {"paths": {"*": ["src/*"]}}}"#,
+    );
+    dir.write("src/a/x.ts", "");
+    let root = canonicalize(&dir).unwrap();
+
+    let resolver = ImportResolver::new(&root).unwrap();
+    let resolve = |specifier: &str| {
+        resolver
+            .resolve_import(specifier, &root.join("src"))
+            .unwrap()
+    };
+    assert_eq!(
+        resolve("a/x"),
+        ResolvedImport::File(PathBuf::from("src/a/x.ts"))
+    );
+    assert_eq!(resolve("fs"), ResolvedImport::External);
+    assert_eq!(resolve("node:fs/promises"), ResolvedImport::External);
+    assert_eq!(resolve("lodash"), ResolvedImport::External);
+    assert_eq!(resolve("a/missing"), ResolvedImport::External);
+}
@@ -6,27 +6,104 @@ use std::collections::BTreeMap;
 use std::error::Error;
 use std::io::Write;
 
-use crate::violations::ReferenceToNonexistentDirectory;
+use crate::{
+    environment::Environment,
+    glob::{is_glob, segment_match},
+    packages::PackageRules,
+    violations::ReferenceToNonexistentDirectory,
+};
 
 pub const RULES_FILE_NAME: &str = ".deplint.rules.yml";
 
+/// How siblings that a child neither allows nor denies are treated.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DefaultPolicy {
+    Allow,
+    Deny,
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct Rules {
+    /// When not set, children may only import the siblings they allow among
+    /// those mentioned in `allow` and `allow_types`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default: Option<DefaultPolicy>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
     pub allow: BTreeMap<String, Vec<String>>,
+    /// Additional siblings that a directory may import types from, on top
+    /// of those in `allow`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub allow_types: BTreeMap<String, Vec<String>>,
+    /// Siblings that a child may not import unless it allows them.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub deny: BTreeMap<String, Vec<String>>,
+    /// Children ordered from top to bottom, each of which may import the
+    /// layers below it.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub layers: Vec<String>,
+    /// Only lets layers import the layer directly below them.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub strict: bool,
+    /// Disallows children from importing each other, including children
+    /// that aren't mentioned, unless they allow them.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub isolate: bool,
+    /// Children that every child may import despite `isolate`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub isolate_exceptions: Vec<String>,
+    /// Directories outside of the package root, relative to this directory,
+    /// that code in this directory and its descendants may import.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allow_external: Vec<String>,
+    /// Third-party packages that children, and their descendants, may
+    /// import.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub packages: BTreeMap<String, PackageRules>,
+    /// The runtime that code in this directory and its descendants runs in,
+    /// unless overridden further down.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub environment: Option<Environment>,
+    /// Root-relative directories, or glob patterns of them, whose files may
+    /// import this directory and its descendants. When not set, any file may.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub visibility: Option<Vec<String>>,
 }
 
 impl Rules {
-    // Constructor that takes allow as a parameter.
-    pub fn new_with_allow(allow: BTreeMap<String, Vec<String>>) -> Self {
-        Self { allow }
-    }
-
     /// Returns a vector of sibling directory names that code in the
-    /// passed-in directory is disallowed to import.
-    pub fn get_disallowed_siblings(&self, dirname: &str) -> Option<Vec<&str>> {
-        let unique_dirs = self.extract_unique_dirs();
-        let allowed_dirs = self.get_allowed_siblings(dirname).unwrap_or(vec![]);
-        let diff = find_difference(&unique_dirs, &allowed_dirs);
+    /// passed-in directory is disallowed to import. Type-only imports are
+    /// additionally permitted to import siblings listed in `allow_types`.
+    /// Siblings listed in `allow` take precedence over those in `deny`.
+    pub fn get_disallowed_siblings<'a>(
+        &'a self,
+        dirname: &str,
+        siblings: &'a [String],
+        type_only: bool,
+    ) -> Option<Vec<&'a str>> {
+        let mut allowed_dirs = expand_names(matching_values(&self.allow, dirname), siblings);
+        if type_only {
+            allowed_dirs.extend(expand_names(
+                matching_values(&self.allow_types, dirname),
+                siblings,
+            ));
+        }
+        allowed_dirs.extend(expand_names(self.layers_below(dirname), siblings));
+        let default = if self.isolate {
+            allowed_dirs.extend(expand_names(&self.isolate_exceptions, siblings));
+            Some(DefaultPolicy::Deny)
+        } else {
+            self.default
+        };
+        let mut denied_dirs = match default {
+            None => self.extract_unique_dirs(siblings),
+            Some(DefaultPolicy::Deny) => siblings.iter().map(String::as_str).collect(),
+            Some(DefaultPolicy::Allow) => vec![],
+        };
+        denied_dirs.extend(expand_names(matching_values(&self.deny, dirname), siblings));
+        denied_dirs.sort();
+        denied_dirs.dedup();
+        let diff = find_difference(&denied_dirs, &allowed_dirs);
         let diff = diff
             .into_iter()
             .filter(|x| *x != dirname)
@@ -34,23 +111,78 @@ impl Rules {
         Some(diff)
     }
 
-    fn extract_unique_dirs(&self) -> Vec<&str> {
+    fn extract_unique_dirs<'a>(&'a self, siblings: &'a [String]) -> Vec<&'a str> {
         let mut unique_names = Vec::with_capacity(self.allow.len());
-        for (key, names) in self.allow.iter() {
-            unique_names.push(key.as_str());
-            for name in names {
-                unique_names.push(name.as_str());
-            }
+        for (key, names) in self.allow.iter().chain(self.allow_types.iter()) {
+            unique_names.extend(expand_names([key], siblings));
+            unique_names.extend(expand_names(names, siblings));
         }
+        unique_names.extend(expand_names(&self.layers, siblings));
         unique_names.sort();
         unique_names.dedup();
         unique_names
     }
 
-    fn get_allowed_siblings(&self, dirname: &str) -> Option<Vec<&str>> {
-        let siblings = self.allow.get(dirname)?;
-        Some(siblings.iter().map(|s| s.as_str()).collect())
+    /// Returns the layers that the first layer matching the passed-in
+    /// directory name may import.
+    fn layers_below(&self, dirname: &str) -> &[String] {
+        match self
+            .layers
+            .iter()
+            .position(|layer| segment_match(layer, dirname))
+        {
+            Some(index) => self.layers_below_index(index),
+            None => &[],
+        }
+    }
+
+    /// Returns each layer together with the layers it may import.
+    pub fn layer_allows(&self) -> impl Iterator<Item = (&String, &[String])> {
+        self.layers
+            .iter()
+            .enumerate()
+            .map(|(index, layer)| (layer, self.layers_below_index(index)))
+    }
+
+    fn layers_below_index(&self, index: usize) -> &[String] {
+        let below = &self.layers[index + 1..];
+        if self.strict {
+            &below[..below.len().min(1)]
+        } else {
+            below
+        }
+    }
+}
+
+/// Returns the values of the keys of the passed-in map that match the
+/// directory name, either literally or as a glob pattern like `feature-*`.
+fn matching_values<'a>(map: &'a BTreeMap<String, Vec<String>>, dirname: &str) -> Vec<&'a String> {
+    map.iter()
+        .filter(|(key, _)| segment_match(key, dirname))
+        .flat_map(|(_, values)| values)
+        .collect()
+}
+
+/// Expands the glob patterns among the passed-in directory names to the
+/// sibling directories they match.
+pub(crate) fn expand_names<'a>(
+    names: impl IntoIterator<Item = &'a String>,
+    siblings: &'a [String],
+) -> Vec<&'a str> {
+    let mut expanded = vec![];
+    for name in names {
+        if is_glob(name) {
+            expanded.extend(
+                siblings
+                    .iter()
+                    .filter(|sibling| segment_match(name, sibling))
+                    .map(String::as_str),
+            );
+        } else {
+            expanded.push(name.as_str());
+        }
     }
+    expanded
 }
 
 fn find_difference<'a>(a: &[&'a str], b: &[&'a str]) -> Vec<&'a str> {
@@ -82,16 +214,21 @@ fn lint_rules_file(
 ) -> Vec<ReferenceToNonexistentDirectory> {
     let mut issues = vec![];
     let relative_rules_path = rules_path.strip_prefix(root).unwrap_or(rules_path);
-    for (source, targets) in &rules.allow {
+    for (source, targets) in rules
+        .allow
+        .iter()
+        .chain(rules.allow_types.iter())
+        .chain(rules.deny.iter())
+    {
         let source_path = Path::new(dir_path).join(source);
-        if !source_path.is_dir() {
+        if !is_glob(source) && !source_path.is_dir() {
             issues.push(ReferenceToNonexistentDirectory {
                 directory_name: source.to_string(),
                 file_path: relative_rules_path.to_str().unwrap().to_string(),
             })
         }
         for target in targets {
-            if target == "-" {
+            if target == "-" || is_glob(target) {
                 continue;
             }
             let target_path = Path::new(dir_path).join(target);
@@ -103,6 +240,38 @@ fn lint_rules_file(
             }
         }
     }
+    for layer in rules.layers.iter().chain(&rules.isolate_exceptions) {
+        if !is_glob(layer) && !Path::new(dir_path).join(layer).is_dir() {
+            issues.push(ReferenceToNonexistentDirectory {
+                directory_name: layer.to_string(),
+                file_path: relative_rules_path.to_str().unwrap().to_string(),
+            });
+        }
+    }
+    for source in rules.packages.keys() {
+        if !Path::new(dir_path).join(source).is_dir() {
+            issues.push(ReferenceToNonexistentDirectory {
+                directory_name: source.to_string(),
+                file_path: relative_rules_path.to_str().unwrap().to_string(),
+            });
+        }
+    }
+    for visible_to in rules.visibility.iter().flatten() {
+        if !is_glob(visible_to) && !root.join(visible_to).is_dir() {
+            issues.push(ReferenceToNonexistentDirectory {
+                directory_name: visible_to.to_string(),
+                file_path: relative_rules_path.to_str().unwrap().to_string(),
+            });
+        }
+    }
+    for external in &rules.allow_external {
+        if !Path::new(dir_path).join(external).is_dir() {
+            issues.push(ReferenceToNonexistentDirectory {
+                directory_name: external.to_string(),
+                file_path: relative_rules_path.to_str().unwrap().to_string(),
+            });
+        }
+    }
     issues
 }
 
@@ -112,18 +281,171 @@ pub fn read_rules_file(path: &Path) -> Result<Rules, Box<dyn Error>> {
     Ok(rules)
 }
 
-pub fn write_formatted_rules_file(path: &Path, rules: Rules) -> Result<(), Box<dyn Error>> {
+pub fn write_formatted_rules_file(path: &Path, mut rules: Rules) -> Result<(), Box<dyn Error>> {
     let mut f = File::create(path)?;
-    // Sort the keys within the allow map.
-    let mut new_allow = BTreeMap::new();
-    for (key, mut values) in rules.allow.into_iter() {
+    // Sort the values within the allow maps. The order of layers matters.
+    for values in rules
+        .allow
+        .values_mut()
+        .chain(rules.allow_types.values_mut())
+        .chain(rules.deny.values_mut())
+    {
         values.sort();
-        new_allow.insert(key, values);
     }
-    let new_rules = Rules::new_with_allow(new_allow);
-    let yaml_content = serde_yaml::to_string(&new_rules)?;
+    rules.isolate_exceptions.sort();
+    if let Some(visibility) = &mut rules.visibility {
+        visibility.sort();
+    }
+    for package_rules in rules.packages.values_mut() {
+        package_rules.allow.sort();
+        package_rules.deny.sort();
+    }
+    let yaml_content = serde_yaml::to_string(&rules)?;
     // Replace " with '.
     let yaml_content = yaml_content.replace("\"", "'");
     f.write_all(yaml_content.as_bytes())?;
     Ok(())
 }
+
+#[test]
+fn test_get_disallowed_siblings_for_type_imports() {
+    let rules: Rules =
+        serde_yaml::from_str("allow:\n  app: [domain]\nallow_types:\n  app: [billing]\n").unwrap();
+    let siblings = ["app", "billing", "domain"].map(String::from);
+    assert_eq!(
+        rules.get_disallowed_siblings("app", &siblings, false),
+        Some(vec!["billing"])
+    );
+    assert_eq!(
+        rules.get_disallowed_siblings("app", &siblings, true),
+        Some(vec![])
+    );
+    assert_eq!(
+        rules.get_disallowed_siblings("domain", &siblings, true),
+        Some(vec!["app", "billing"])
+    );
+}
+
+#[test]
+fn test_get_disallowed_siblings_with_patterns() {
+    let siblings = [
+        "feature-billing",
+        "feature-ledger",
+        "shared",
+        "ui-kit",
+        "legacy",
+    ]
+    .map(String::from);
+    let rules: Rules = serde_yaml::from_str(
+        "allow:\n  feature-*: [shared, ui-*]\n  feature-ledger: [feature-billing]\n  '*': [shared]\n",
+    )
+    .unwrap();
+    assert_eq!(
+        rules.get_disallowed_siblings("feature-billing", &siblings, false),
+        Some(vec!["feature-ledger", "legacy"])
+    );
+    assert_eq!(
+        rules.get_disallowed_siblings("feature-ledger", &siblings, false),
+        Some(vec!["legacy"])
+    );
+    assert_eq!(
+        rules.get_disallowed_siblings("ui-kit", &siblings, false),
+        Some(vec!["feature-billing", "feature-ledger", "legacy"])
+    );
+    assert_eq!(
+        rules.get_disallowed_siblings("legacy", &siblings, false),
+        Some(vec!["feature-billing", "feature-ledger", "ui-kit"])
+    );
+}
+
+#[test]
+fn test_get_disallowed_siblings_with_deny() {
+    let siblings = ["app", "domain", "legacy", "migration"].map(String::from);
+    let rules: Rules = serde_yaml::from_str(
+        "default: allow\ndeny:\n  '*': [legacy]\n  domain: [app]\nallow:\n  migration: [legacy]\n",
+    )
+    .unwrap();
+    assert_eq!(
+        rules.get_disallowed_siblings("app", &siblings, false),
+        Some(vec!["legacy"])
+    );
+    assert_eq!(
+        rules.get_disallowed_siblings("domain", &siblings, false),
+        Some(vec!["app", "legacy"])
+    );
+    assert_eq!(
+        rules.get_disallowed_siblings("migration", &siblings, false),
+        Some(vec![])
+    );
+
+    let rules: Rules = serde_yaml::from_str("default: deny\nallow:\n  app: [domain]\n").unwrap();
+    assert_eq!(
+        rules.get_disallowed_siblings("app", &siblings, false),
+        Some(vec!["legacy", "migration"])
+    );
+    assert_eq!(
+        rules.get_disallowed_siblings("legacy", &siblings, false),
+        Some(vec!["app", "domain", "migration"])
+    );
+}
+
+#[test]
+fn test_get_disallowed_siblings_with_layers() {
+    let siblings = ["app", "domain", "infra", "scripts", "ui"].map(String::from);
+    let rules: Rules =
+        serde_yaml::from_str("layers: [ui, app, domain, infra]\nallow:\n  scripts: [infra]\n")
+            .unwrap();
+    assert_eq!(
+        rules.get_disallowed_siblings("ui", &siblings, false),
+        Some(vec!["scripts"])
+    );
+    assert_eq!(
+        rules.get_disallowed_siblings("domain", &siblings, false),
+        Some(vec!["app", "scripts", "ui"])
+    );
+    assert_eq!(
+        rules.get_disallowed_siblings("scripts", &siblings, false),
+        Some(vec!["app", "domain", "ui"])
+    );
+
+    let rules: Rules =
+        serde_yaml::from_str("layers: [ui, app, domain, infra]\nstrict: true\n").unwrap();
+    assert_eq!(
+        rules.get_disallowed_siblings("ui", &siblings, false),
+        Some(vec!["domain", "infra"])
+    );
+    assert_eq!(
+        rules.get_disallowed_siblings("infra", &siblings, false),
+        Some(vec!["app", "domain", "ui"])
+    );
+}
+
+#[test]
+fn test_get_disallowed_siblings_with_isolate() {
+    let siblings = ["billing", "ledger", "reports", "shared"].map(String::from);
+    let rules: Rules = serde_yaml::from_str(
+        "isolate: true\nisolate_exceptions: [shared]\nallow:\n  reports: [ledger]\n",
+    )
+    .unwrap();
+    assert_eq!(
+        rules.get_disallowed_siblings("billing", &siblings, false),
+        Some(vec!["ledger", "reports"])
+    );
+    assert_eq!(
+        rules.get_disallowed_siblings("reports", &siblings, false),
+        Some(vec!["billing"])
+    );
+    assert_eq!(
+        rules.get_disallowed_siblings("shared", &siblings, false),
+        Some(vec!["billing", "ledger", "reports"])
+    );
+}
+
+#[test]
+fn test_serialize_rules_without_allow() {
+    let rules: Rules = serde_yaml::from_str("layers: [ui, app]\nisolate: true\n").unwrap();
+    assert_eq!(
+        serde_yaml::to_string(&rules).unwrap(),
+        "layers:\n- ui\n- app\nisolate: true\n"
+    );
+}
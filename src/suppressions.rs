@@ -0,0 +1,277 @@
+use std::ops::Range;
+use std::path::Path;
+
+use crate::ts_reader::TsImport;
+
+/// The legacy directive, equivalent to `ts_deplint-disable-next-line`.
+const IGNORE_DIRECTIVE: &str = "ts_deplint ignore";
+const DISABLE_NEXT_LINE_DIRECTIVE: &str = "ts_deplint-disable-next-line";
+const DISABLE_FILE_DIRECTIVE: &str = "ts_deplint-disable-file";
+const DISABLE_DIRECTIVE: &str = "ts_deplint-disable";
+const ENABLE_DIRECTIVE: &str = "ts_deplint-enable";
+
+/// The lines of a file covered by a suppression comment.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SuppressionScope {
+    /// `// ts_deplint-disable-next-line` or `// ts_deplint ignore`.
+    NextLine,
+    /// `// ts_deplint-disable` up to the matching `// ts_deplint-enable`, or
+    /// the end of the file if there is none.
+    Region {
+        end_line: Option<usize>,
+        /// Byte range of the matching `// ts_deplint-enable` comment.
+        end_span: Option<Range<usize>>,
+    },
+    /// `// ts_deplint-disable-file` in the file header.
+    File,
+}
+
+/// A comment that suppresses violations, like
+/// `// ts_deplint-disable-next-line src/billing -- Migration in progress.`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Suppression {
+    pub scope: SuppressionScope,
+    /// The root-relative directory that violations must import to be
+    /// suppressed. Suppresses every violation if unset.
+    pub target: Option<String>,
+    /// The text following `--`, explaining why the suppression is needed.
+    pub reason: Option<String>,
+    /// 1-based line number of the end of the comment.
+    pub line: usize,
+    /// Byte range of the comment within the file.
+    pub span: Range<usize>,
+}
+
+impl Suppression {
+    /// Returns whether the suppression covers the passed-in import, whose
+    /// root-relative path is `import_path`.
+    pub fn suppresses(&self, import: &TsImport, import_path: &Path) -> bool {
+        let covers_line = match self.scope {
+            // Comments right above the `from` clause of a multi-line import
+            // also cover it, as the legacy directive always has.
+            SuppressionScope::NextLine => {
+                import.line == self.line + 1 || import.specifier_line == self.line + 1
+            }
+            SuppressionScope::Region { end_line, .. } => {
+                import.line > self.line && end_line.is_none_or(|end_line| import.line < end_line)
+            }
+            SuppressionScope::File => true,
+        };
+        covers_line
+            && self
+                .target
+                .as_ref()
+                .is_none_or(|target| import_path.starts_with(target))
+    }
+
+    /// Returns the byte range of the `// ts_deplint-enable` comment ending
+    /// the suppression, if any.
+    pub fn end_span(&self) -> Option<&Range<usize>> {
+        match &self.scope {
+            SuppressionScope::Region { end_span, .. } => end_span.as_ref(),
+            SuppressionScope::NextLine | SuppressionScope::File => None,
+        }
+    }
+}
+
+/// A directive parsed from a single comment. Enable directives only end the
+/// regions of earlier disable directives.
+enum Directive {
+    Disable(Suppression),
+    Enable {
+        target: Option<String>,
+        line: usize,
+        span: Range<usize>,
+    },
+}
+
+/// A comment as seen by the lexer.
+pub struct SuppressionComment<'a> {
+    pub text: &'a str,
+    pub end_line: usize,
+    pub span: Range<usize>,
+    /// Whether the comment precedes every token of the file.
+    pub in_header: bool,
+}
+
+/// Returns the suppressions declared by the passed-in comments, which must
+/// be in source order.
+pub fn parse_suppressions(comments: &[SuppressionComment]) -> Vec<Suppression> {
+    let mut suppressions: Vec<Suppression> = Vec::new();
+    for comment in comments {
+        match parse_directive(comment) {
+            Some(Directive::Disable(suppression)) => suppressions.push(suppression),
+            Some(Directive::Enable { target, line, span }) => {
+                for suppression in suppressions.iter_mut() {
+                    if let SuppressionScope::Region { end_line, end_span } = &mut suppression.scope
+                    {
+                        if end_line.is_none() && (target.is_none() || target == suppression.target)
+                        {
+                            *end_line = Some(line);
+                            *end_span = Some(span.clone());
+                        }
+                    }
+                }
+            }
+            None => {}
+        }
+    }
+    suppressions
+}
+
+fn parse_directive(comment: &SuppressionComment) -> Option<Directive> {
+    let body = comment
+        .text
+        .trim_start_matches("//")
+        .trim_start_matches("/*")
+        .trim_end_matches("*/")
+        .trim_start_matches(['*', ' ', '\t']);
+    if let Some(rest) = body.strip_prefix(IGNORE_DIRECTIVE) {
+        // The legacy directive allowed any text after it, so it never takes
+        // a target.
+        let (_, reason) = parse_target_and_reason(rest);
+        return Some(Directive::Disable(Suppression {
+            scope: SuppressionScope::NextLine,
+            target: None,
+            reason,
+            line: comment.end_line,
+            span: comment.span.clone(),
+        }));
+    }
+    let (scope, rest) = if let Some(rest) = strip_directive(body, DISABLE_NEXT_LINE_DIRECTIVE) {
+        (SuppressionScope::NextLine, rest)
+    } else if let Some(rest) = strip_directive(body, DISABLE_FILE_DIRECTIVE) {
+        if !comment.in_header {
+            return None;
+        }
+        (SuppressionScope::File, rest)
+    } else if let Some(rest) = strip_directive(body, DISABLE_DIRECTIVE) {
+        (
+            SuppressionScope::Region {
+                end_line: None,
+                end_span: None,
+            },
+            rest,
+        )
+    } else if let Some(rest) = strip_directive(body, ENABLE_DIRECTIVE) {
+        let (target, _) = parse_target_and_reason(rest);
+        return Some(Directive::Enable {
+            target,
+            line: comment.end_line,
+            span: comment.span.clone(),
+        });
+    } else {
+        return None;
+    };
+    let (target, reason) = parse_target_and_reason(rest);
+    Some(Directive::Disable(Suppression {
+        scope,
+        target,
+        reason,
+        line: comment.end_line,
+        span: comment.span.clone(),
+    }))
+}
+
+/// Strips the passed-in directive from the start of the comment body,
+/// making sure it isn't merely a prefix of a longer word.
+fn strip_directive<'a>(body: &'a str, directive: &str) -> Option<&'a str> {
+    let rest = body.strip_prefix(directive)?;
+    if rest.is_empty() || rest.starts_with(char::is_whitespace) {
+        Some(rest)
+    } else {
+        None
+    }
+}
+
+fn parse_target_and_reason(rest: &str) -> (Option<String>, Option<String>) {
+    let (target, reason) = match rest.split_once("--") {
+        Some((target, reason)) => (target, Some(reason)),
+        None => (rest, None),
+    };
+    let target = target
+        .split_whitespace()
+        .next()
+        .map(|target| target.trim_end_matches('/').to_string());
+    let reason = reason
+        .map(str::trim)
+        .filter(|reason| !reason.is_empty())
+        .map(String::from);
+    (target, reason)
+}
+
+#[cfg(test)]
+fn parse_source(source: &str) -> Vec<Suppression> {
+    crate::ts_reader::parse_ts_file(source).suppressions
+}
+
+#[test]
+fn test_parse_suppressions() {
+    let source = "// ts_deplint-disable-file src/legacy -- Being deleted.\nimport a from 'a';\n// ts_deplint-disable-next-line\nimport b from 'b';\n/* ts_deplint-disable src/billing -- Migration. */\nimport c from 'c';\n// ts_deplint-enable src/billing\n// ts_deplint-disable-file\n// ts_deplint-disabled\n";
+    assert_eq!(
+        parse_source(source),
+        vec![
+            Suppression {
+                scope: SuppressionScope::File,
+                target: Some("src/legacy".to_string()),
+                reason: Some("Being deleted.".to_string()),
+                line: 1,
+                span: 0..55,
+            },
+            Suppression {
+                scope: SuppressionScope::NextLine,
+                target: None,
+                reason: None,
+                line: 3,
+                span: 75..106,
+            },
+            Suppression {
+                scope: SuppressionScope::Region {
+                    end_line: Some(7),
+                    end_span: Some(196..228),
+                },
+                target: Some("src/billing".to_string()),
+                reason: Some("Migration.".to_string()),
+                line: 5,
+                span: 126..176,
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_suppresses() {
+    let source = "// ts_deplint-disable src/billing\nimport a from 'a';\n// ts_deplint-enable\nimport b from 'b';";
+    let ts_file = crate::ts_reader::parse_ts_file(source);
+    let region = &ts_file.suppressions[0];
+    let (a, b) = (&ts_file.imports[0], &ts_file.imports[1]);
+    assert!(region.suppresses(a, Path::new("src/billing/ledger")));
+    assert!(!region.suppresses(a, Path::new("src/billing-v2/ledger")));
+    assert!(!region.suppresses(b, Path::new("src/billing/ledger")));
+}
+
+#[test]
+fn test_parse_legacy_ignore_with_free_text() {
+    let source = "// ts_deplint ignore legacy import from b\nimport b from 'b';\n// ts_deplint ignore src/billing -- Migration.\nimport c from 'c';\n";
+    let suppressions = parse_source(source);
+    assert_eq!(
+        suppressions
+            .iter()
+            .map(|s| (&s.scope, s.target.as_deref(), s.reason.as_deref()))
+            .collect::<Vec<_>>(),
+        vec![
+            (&SuppressionScope::NextLine, None, None),
+            (&SuppressionScope::NextLine, None, Some("Migration.")),
+        ]
+    );
+}
+
+#[test]
+fn test_suppresses_multi_line_import() {
+    let source = "import {\n  c,\n  // ts_deplint ignore\n} from '../c/z';\nimport {\n  d,\n} from '../d/z';\n";
+    let ts_file = crate::ts_reader::parse_ts_file(source);
+    let suppression = &ts_file.suppressions[0];
+    let (c, d) = (&ts_file.imports[0], &ts_file.imports[1]);
+    assert!(suppression.suppresses(c, Path::new("c/z")));
+    assert!(!suppression.suppresses(d, Path::new("d/z")));
+}
@@ -0,0 +1,58 @@
+use std::{
+    fs,
+    ops::Deref,
+    path::{Path, PathBuf},
+    process,
+};
+
+/// A directory of files that tests create on disk, which is removed when it
+/// goes out of scope, even if the test panics.
+pub struct TempDir {
+    path: PathBuf,
+}
+
+impl TempDir {
+    /// Creates an empty directory whose name is unique to the passed-in name
+    /// and the running process.
+    pub fn new(name: &str) -> TempDir {
+        let path = std::env::temp_dir().join(format!("ts_deplint_{}_{}", name, process::id()));
+        let _ = fs::remove_dir_all(&path);
+        fs::create_dir_all(&path).unwrap();
+        TempDir { path }
+    }
+
+    /// Creates the passed-in directories, relative to this directory.
+    pub fn create_dirs(&self, dirs: &[&str]) {
+        for dir in dirs {
+            fs::create_dir_all(self.path.join(dir)).unwrap();
+        }
+    }
+
+    /// Writes the passed-in file, relative to this directory, creating its
+    /// parent directories.
+    pub fn write(&self, file: &str, contents: &str) {
+        let path = self.path.join(file);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+    }
+}
+
+impl Deref for TempDir {
+    type Target = Path;
+
+    fn deref(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl AsRef<Path> for TempDir {
+    fn as_ref(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.path);
+    }
+}
@@ -1,42 +1,632 @@
-use regex::Regex;
-use std::fs::File;
-use std::io::{self, BufRead};
+use std::fs;
+use std::io;
+use std::ops::Range;
 use std::path::Path;
-use std::sync::LazyLock;
 
-const IGNORE_COMMENT: &str = "// ts_deplint ignore";
+use crate::suppressions::{self, Suppression, SuppressionComment};
 
-pub fn read_ts_imports(ts_path: &Path) -> io::Result<Vec<String>> {
-    let ts_file = File::open(ts_path)?;
-    let reader = io::BufReader::new(ts_file);
+/// The syntactic form through which a module is imported.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum ImportKind {
+    /// `import x from 'foo';` or `import 'foo';`.
+    Static,
+    /// `import('foo')`.
+    Dynamic,
+    /// `require('foo')` or `import x = require('foo');`.
+    Require,
+    /// `export * from 'foo';` or `export { x } from 'foo';`.
+    ReExport,
+}
+
+impl ImportKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ImportKind::Static => "import",
+            ImportKind::Dynamic => "dynamic import",
+            ImportKind::Require => "require",
+            ImportKind::ReExport => "re-export",
+        }
+    }
+}
+
+/// A module specifier referenced by an import statement or expression.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TsImport {
+    /// The module specifier as written, e.g. `./foo` or `src/bar`.
+    pub specifier: String,
+    pub kind: ImportKind,
+    /// Whether the declaration only imports types, like `import type { T }
+    /// from 'foo';`, `export { type T } from 'foo';` or `typeof
+    /// import('foo')`. Such imports are erased at compile time.
+    pub is_type_only: bool,
+    /// Byte offset of the start of the statement within the file.
+    pub offset: usize,
+    /// 1-based line number of the start of the statement.
+    pub line: usize,
+    /// 1-based line number of the specifier, which differs from `line` for
+    /// imports spanning several lines.
+    pub specifier_line: usize,
+    /// 1-based column, in characters, of the start of the statement.
+    pub column: usize,
+    /// Byte range from the start of the statement to the end of the
+    /// specifier's string literal.
+    pub span: Range<usize>,
+}
+
+/// The imports and suppression comments of a source file.
+pub struct TsFile {
+    pub imports: Vec<TsImport>,
+    pub suppressions: Vec<Suppression>,
+}
 
-    let mut ts_imports = Vec::new();
+pub fn read_ts_file(ts_path: &Path) -> io::Result<TsFile> {
+    let source = fs::read_to_string(ts_path)?;
+    Ok(parse_ts_file(&source))
+}
+
+pub fn parse_ts_file(source: &str) -> TsFile {
+    let (tokens, comments) = Lexer::new(source).run();
+    TsFile {
+        imports: find_imports(source, &tokens),
+        suppressions: suppressions::parse_suppressions(&comments),
+    }
+}
+
+pub fn read_ts_imports(ts_path: &Path) -> io::Result<Vec<TsImport>> {
+    let source = fs::read_to_string(ts_path)?;
+    Ok(extract_imports(&source))
+}
+
+/// Extracts every import in the passed-in source, skipping imports covered
+/// by a suppression comment without a target.
+pub fn extract_imports(source: &str) -> Vec<TsImport> {
+    let ts_file = parse_ts_file(source);
+    ts_file
+        .imports
+        .into_iter()
+        .filter(|import| {
+            !ts_file.suppressions.iter().any(|suppression| {
+                suppression.target.is_none() && suppression.suppresses(import, Path::new(""))
+            })
+        })
+        .collect()
+}
 
-    let mut curr_line: String = "".to_string();
-    let mut prev_line: String;
-    for line in reader.lines() {
-        prev_line = curr_line;
-        curr_line = line?;
+#[derive(Debug, PartialEq)]
+enum Token<'a> {
+    /// An identifier, keyword or numeric literal.
+    Word(&'a str),
+    /// A string literal, unescaped.
+    Str(String),
+    /// A part of a template literal. Only templates without substitutions
+    /// carry their value.
+    Template(Option<String>),
+    Regex,
+    Punct(u8),
+}
+
+struct Lexeme<'a> {
+    token: Token<'a>,
+    offset: usize,
+    end: usize,
+    line: usize,
+}
+
+/// Keywords after which a `/` starts a regular expression rather than a
+/// division.
+const REGEX_PRECEDING_KEYWORDS: &[&str] = &[
+    "await",
+    "case",
+    "delete",
+    "do",
+    "else",
+    "in",
+    "instanceof",
+    "new",
+    "of",
+    "return",
+    "throw",
+    "typeof",
+    "void",
+    "yield",
+];
+
+/// Methods of the promise returned by a dynamic import.
+const PROMISE_METHODS: &[&str] = &["catch", "finally", "then"];
+
+/// A scanner that splits TypeScript source into the tokens needed to find
+/// imports. It understands comments, string, template and regular expression
+/// literals well enough to never mistake their contents for code.
+struct Lexer<'a> {
+    source: &'a str,
+    bytes: &'a [u8],
+    pos: usize,
+    line: usize,
+    tokens: Vec<Lexeme<'a>>,
+    comments: Vec<SuppressionComment<'a>>,
+    brace_depth: usize,
+    // Brace depths at which template literal substitutions were opened.
+    template_depths: Vec<usize>,
+}
+
+impl<'a> Lexer<'a> {
+    fn new(source: &'a str) -> Self {
+        Self {
+            source,
+            bytes: source.as_bytes(),
+            pos: 0,
+            line: 1,
+            tokens: Vec::new(),
+            comments: Vec::new(),
+            brace_depth: 0,
+            template_depths: Vec::new(),
+        }
+    }
+
+    fn run(mut self) -> (Vec<Lexeme<'a>>, Vec<SuppressionComment<'a>>) {
+        if self.source.starts_with("#!") {
+            self.line_comment();
+        }
+        while let Some(&b) = self.bytes.get(self.pos) {
+            let start = self.pos;
+            match b {
+                b'\n' => {
+                    self.line += 1;
+                    self.pos += 1;
+                }
+                b' ' | b'\t' | b'\r' | 0x0b | 0x0c => self.pos += 1,
+                b'/' if self.peek(1) == Some(b'/') => self.line_comment(),
+                b'/' if self.peek(1) == Some(b'*') => self.block_comment(),
+                b'/' if self.regex_allowed() => self.regex(),
+                b'\'' | b'"' => self.string(b),
+                b'`' => {
+                    self.pos += 1;
+                    self.template(start, true);
+                }
+                b'{' => {
+                    self.brace_depth += 1;
+                    self.punct(b);
+                }
+                b'}' if self.template_depths.last() == Some(&self.brace_depth) => {
+                    self.template_depths.pop();
+                    self.pos += 1;
+                    self.template(start, false);
+                }
+                b'}' => {
+                    self.brace_depth = self.brace_depth.saturating_sub(1);
+                    self.punct(b);
+                }
+                _ if is_word_byte(b) => self.word(),
+                _ => self.punct(b),
+            }
+        }
+        (self.tokens, self.comments)
+    }
+
+    fn peek(&self, ahead: usize) -> Option<u8> {
+        self.bytes.get(self.pos + ahead).copied()
+    }
 
-        if prev_line.contains(IGNORE_COMMENT) {
+    fn push(&mut self, token: Token<'a>, offset: usize, line: usize) {
+        self.tokens.push(Lexeme {
+            token,
+            offset,
+            end: self.pos,
+            line,
+        });
+    }
+
+    fn punct(&mut self, b: u8) {
+        self.pos += 1;
+        self.push(Token::Punct(b), self.pos - 1, self.line);
+    }
+
+    fn word(&mut self) {
+        let start = self.pos;
+        while self.peek(0).is_some_and(is_word_byte) {
+            self.pos += 1;
+        }
+        self.push(Token::Word(&self.source[start..self.pos]), start, self.line);
+    }
+
+    fn line_comment(&mut self) {
+        let start = self.pos;
+        while self.peek(0).is_some_and(|b| b != b'\n') {
+            self.pos += 1;
+        }
+        self.push_comment(start, self.pos);
+    }
+
+    fn block_comment(&mut self) {
+        let start = self.pos;
+        self.pos += 2;
+        while let Some(b) = self.peek(0) {
+            if b == b'*' && self.peek(1) == Some(b'/') {
+                self.pos += 2;
+                break;
+            }
+            if b == b'\n' {
+                self.line += 1;
+            }
+            self.pos += 1;
+        }
+        self.push_comment(start, self.pos.min(self.bytes.len()));
+    }
+
+    fn push_comment(&mut self, start: usize, end: usize) {
+        self.comments.push(SuppressionComment {
+            text: &self.source[start..end],
+            end_line: self.line,
+            span: start..end,
+            in_header: self.tokens.is_empty(),
+        });
+    }
+
+    fn string(&mut self, quote: u8) {
+        let (start, line) = (self.pos, self.line);
+        self.pos += 1;
+        let mut end = self.bytes.len();
+        while let Some(b) = self.peek(0) {
+            match b {
+                b'\\' => {
+                    if self.peek(1) == Some(b'\n') {
+                        self.line += 1;
+                    }
+                    self.pos += 2;
+                }
+                // Unterminated string literal.
+                b'\n' => {
+                    end = self.pos;
+                    break;
+                }
+                _ if b == quote => {
+                    end = self.pos;
+                    self.pos += 1;
+                    break;
+                }
+                _ => self.pos += 1,
+            }
+        }
+        let raw = &self.source[start + 1..end];
+        self.push(Token::Str(unescape(raw)), start, line);
+    }
+
+    /// Scans a template literal from just after its opening backtick, or
+    /// from just after the closing brace of a substitution, up to the
+    /// closing backtick or the next substitution.
+    fn template(&mut self, start: usize, is_head: bool) {
+        let line = self.line;
+        let content_start = self.pos;
+        while let Some(b) = self.peek(0) {
+            match b {
+                b'\\' => {
+                    if self.peek(1) == Some(b'\n') {
+                        self.line += 1;
+                    }
+                    self.pos += 2;
+                }
+                b'`' => {
+                    let value = is_head.then(|| unescape(&self.source[content_start..self.pos]));
+                    self.pos += 1;
+                    self.push(Token::Template(value), start, line);
+                    return;
+                }
+                b'$' if self.peek(1) == Some(b'{') => {
+                    self.pos += 2;
+                    self.template_depths.push(self.brace_depth);
+                    self.push(Token::Template(None), start, line);
+                    return;
+                }
+                b'\n' => {
+                    self.line += 1;
+                    self.pos += 1;
+                }
+                _ => self.pos += 1,
+            }
+        }
+        // Unterminated template literal.
+        self.pos = self.bytes.len();
+        self.push(Token::Template(None), start, line);
+    }
+
+    fn regex(&mut self) {
+        let start = self.pos;
+        self.pos += 1;
+        let mut in_class = false;
+        while let Some(b) = self.peek(0) {
+            match b {
+                b'\\' => self.pos += 2,
+                b'[' => {
+                    in_class = true;
+                    self.pos += 1;
+                }
+                b']' => {
+                    in_class = false;
+                    self.pos += 1;
+                }
+                b'/' if !in_class => {
+                    self.pos += 1;
+                    break;
+                }
+                // Unterminated regular expression literal.
+                b'\n' => break,
+                _ => self.pos += 1,
+            }
+        }
+        // Flags.
+        while self.peek(0).is_some_and(is_word_byte) {
+            self.pos += 1;
+        }
+        self.push(Token::Regex, start, self.line);
+    }
+
+    fn regex_allowed(&self) -> bool {
+        match self.tokens.last().map(|lexeme| &lexeme.token) {
+            None => true,
+            Some(Token::Punct(b)) => !matches!(b, b')' | b']' | b'}'),
+            Some(Token::Word(word)) => REGEX_PRECEDING_KEYWORDS.contains(word),
+            Some(_) => false,
+        }
+    }
+}
+
+fn is_word_byte(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b == b'_' || b == b'$' || b >= 0x80
+}
+
+fn unescape(raw: &str) -> String {
+    let mut value = String::with_capacity(raw.len());
+    let mut chars = raw.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            value.push(c);
             continue;
         }
+        match chars.next() {
+            Some('n') => value.push('\n'),
+            Some('t') => value.push('\t'),
+            Some('r') => value.push('\r'),
+            // Line continuation.
+            Some('\n') | None => {}
+            Some(other) => value.push(other),
+        }
+    }
+    value
+}
 
-        if let Some(ts_import) = extract_import(&curr_line) {
-            ts_imports.push(ts_import);
+fn find_imports(source: &str, tokens: &[Lexeme]) -> Vec<TsImport> {
+    let mut imports = Vec::new();
+    for (i, lexeme) in tokens.iter().enumerate() {
+        let found = if is_keyword_at(tokens, i, "import") {
+            static_import_specifier(tokens, i)
+                .map(|specifier| (specifier, ImportKind::Static))
+                .or_else(|| call_specifier(tokens, i).map(|s| (s, ImportKind::Dynamic)))
+        } else if is_keyword_at(tokens, i, "export") {
+            reexport_specifier(tokens, i).map(|specifier| (specifier, ImportKind::ReExport))
+        } else if is_keyword_at(tokens, i, "require") {
+            call_specifier(tokens, i).map(|specifier| (specifier, ImportKind::Require))
+        } else {
+            None
+        };
+        if let Some((specifier, kind)) = found {
+            let is_type_only = match kind {
+                ImportKind::Static | ImportKind::ReExport => is_type_only_declaration(tokens, i),
+                ImportKind::Require => is_type_only_import_equals(tokens, i),
+                ImportKind::Dynamic => is_import_type(tokens, i),
+            };
+            let line_start = source[..lexeme.offset]
+                .rfind('\n')
+                .map(|i| i + 1)
+                .unwrap_or(0);
+            imports.push(TsImport {
+                specifier: literal_value(specifier).to_string(),
+                kind,
+                is_type_only,
+                offset: lexeme.offset,
+                line: lexeme.line,
+                specifier_line: specifier.line,
+                column: source[line_start..lexeme.offset].chars().count() + 1,
+                span: lexeme.offset..specifier.end,
+            });
         }
     }
+    imports
+}
 
-    Ok(ts_imports)
+fn literal_value<'t>(lexeme: &'t Lexeme) -> &'t str {
+    match &lexeme.token {
+        Token::Str(value) | Token::Template(Some(value)) => value,
+        _ => unreachable!("Specifiers are string literals."),
+    }
 }
 
-static IMPORT_REGEX: LazyLock<Regex> =
-    LazyLock::new(|| Regex::new(r#"(?:from|import)+\s+["']([^"']+)["'];"#).unwrap());
+/// Returns whether the token at the passed-in index is the passed-in keyword
+/// used as such, rather than as a property name like `foo.import` or
+/// `{ import: 1 }`.
+fn is_keyword_at(tokens: &[Lexeme], i: usize, keyword: &str) -> bool {
+    if tokens[i].token != Token::Word(keyword) {
+        return false;
+    }
+    let preceded_by_dot = i > 0 && tokens[i - 1].token == Token::Punct(b'.');
+    let followed_by_colon = matches!(
+        tokens.get(i + 1).map(|lexeme| &lexeme.token),
+        Some(Token::Punct(b':'))
+    );
+    !preceded_by_dot && !followed_by_colon
+}
 
-fn extract_import(line: &str) -> Option<String> {
-    let captures = IMPORT_REGEX.captures(line)?;
-    let group_1 = captures.get(1)?;
-    Some(group_1.as_str().to_string())
+/// Returns the specifier literal of the static import declaration starting
+/// at the passed-in `import` keyword, if it is one.
+fn static_import_specifier<'t, 'a>(
+    tokens: &'t [Lexeme<'a>],
+    start: usize,
+) -> Option<&'t Lexeme<'a>> {
+    let mut i = start + 1;
+    let mut in_braces = false;
+    while let Some(lexeme) = tokens.get(i) {
+        match &lexeme.token {
+            // Side-effect import, `import 'foo';`.
+            Token::Str(_) if i == start + 1 => return Some(lexeme),
+            // String-named specifiers, like `{ "a b" as x }`.
+            Token::Punct(b'{') => in_braces = true,
+            Token::Punct(b'}') => in_braces = false,
+            Token::Str(_) if in_braces => {}
+            Token::Word("from") => {
+                if let Some(
+                    specifier @ Lexeme {
+                        token: Token::Str(_),
+                        ..
+                    },
+                ) = tokens.get(i + 1)
+                {
+                    return Some(specifier);
+                }
+            }
+            // `import(...)`, `import.meta`, `import x = ...` or the end of
+            // the statement without a `from` clause.
+            Token::Punct(b'(' | b'.' | b'=' | b';') => return None,
+            Token::Str(_) | Token::Template(_) | Token::Regex => return None,
+            Token::Word("import" | "export") => return None,
+            _ => {}
+        }
+        i += 1;
+    }
+    None
+}
+
+/// Returns whether the import or re-export declaration starting at the
+/// passed-in keyword only brings in types.
+fn is_type_only_declaration(tokens: &[Lexeme], start: usize) -> bool {
+    match tokens.get(start + 1).map(|lexeme| &lexeme.token) {
+        // `import type from 'foo';` and `import type, { a } from 'foo';`
+        // import a default export named `type`.
+        Some(Token::Word("type")) => !matches!(
+            tokens.get(start + 2).map(|lexeme| &lexeme.token),
+            Some(Token::Word("from") | Token::Punct(b','))
+        ),
+        Some(Token::Punct(b'{')) => has_only_type_specifiers(tokens, start + 1),
+        _ => false,
+    }
+}
+
+/// Returns whether the `require` call at the passed-in index is the value of
+/// a type-only import alias, like `import type A = require('foo');`.
+fn is_type_only_import_equals(tokens: &[Lexeme], callee: usize) -> bool {
+    // `import type = require('foo');` imports a value named `type`.
+    callee >= 4
+        && tokens[callee - 1].token == Token::Punct(b'=')
+        && matches!(tokens[callee - 2].token, Token::Word(_))
+        && tokens[callee - 3].token == Token::Word("type")
+        && is_keyword_at(tokens, callee - 4, "import")
+}
+
+/// Returns whether the `import(...)` at the passed-in index is an import
+/// type, like `typeof import('foo')` or `type A = import('foo').A`, rather
+/// than a dynamic import.
+fn is_import_type(tokens: &[Lexeme], callee: usize) -> bool {
+    let token_at = |i: usize| tokens.get(i).map(|lexeme| &lexeme.token);
+    // Dynamic imports are promises, whose methods aren't types.
+    let has_type_member = token_at(callee + 4) == Some(&Token::Punct(b'.'))
+        && matches!(token_at(callee + 5), Some(Token::Word(member)) if !PROMISE_METHODS.contains(member));
+    match callee.checked_sub(1).and_then(token_at) {
+        Some(Token::Word("typeof")) => true,
+        // Type annotations, type arguments, unions and intersections.
+        Some(Token::Punct(b':' | b'<' | b',' | b'|' | b'&')) => has_type_member,
+        // Type aliases, `type A = import('foo').A`.
+        Some(Token::Punct(b'=')) => {
+            callee >= 3
+                && token_at(callee - 3) == Some(&Token::Word("type"))
+                && matches!(token_at(callee - 2), Some(Token::Word(_)))
+        }
+        _ => false,
+    }
+}
+
+/// Returns whether every specifier in the braces opened at the passed-in
+/// index carries an inline `type` modifier, like `{ type A, type B as C }`.
+fn has_only_type_specifiers(tokens: &[Lexeme], open: usize) -> bool {
+    let Some(close) = tokens[open..]
+        .iter()
+        .position(|lexeme| lexeme.token == Token::Punct(b'}'))
+    else {
+        return false;
+    };
+    let specifiers = tokens[open + 1..open + close]
+        .split(|lexeme| lexeme.token == Token::Punct(b','))
+        .filter(|specifier| !specifier.is_empty())
+        .collect::<Vec<_>>();
+    !specifiers.is_empty()
+        && specifiers.iter().all(|specifier| {
+            let words = specifier
+                .iter()
+                .map(|lexeme| &lexeme.token)
+                .collect::<Vec<_>>();
+            // `{ type }` and `{ type as t }` import a value named `type`.
+            words[0] == &Token::Word("type")
+                && words.len() > 1
+                && !(words.len() == 3 && words[1] == &Token::Word("as"))
+        })
+}
+
+/// Returns the specifier literal of the re-export declaration starting at
+/// the passed-in `export` keyword, if it is one.
+fn reexport_specifier<'t, 'a>(tokens: &'t [Lexeme<'a>], start: usize) -> Option<&'t Lexeme<'a>> {
+    let mut i = start + 1;
+    if tokens.get(i)?.token == Token::Word("type") {
+        i += 1;
+    }
+    match tokens.get(i)?.token {
+        // `export * from 'foo';` or `export * as foo from 'foo';`.
+        Token::Punct(b'*') => {
+            i += 1;
+            if tokens.get(i)?.token == Token::Word("as") {
+                i += 2;
+            }
+        }
+        // `export { a, b as c } from 'foo';`.
+        Token::Punct(b'{') => {
+            let close = tokens[i..]
+                .iter()
+                .position(|lexeme| lexeme.token == Token::Punct(b'}'))?;
+            i += close + 1;
+        }
+        _ => return None,
+    }
+    if tokens.get(i)?.token != Token::Word("from") {
+        return None;
+    }
+    let specifier = tokens.get(i + 1)?;
+    match specifier.token {
+        Token::Str(_) => Some(specifier),
+        _ => None,
+    }
+}
+
+/// Returns the specifier literal of the `import(...)` or `require(...)` call
+/// whose callee is at the passed-in index, if its argument is a string
+/// literal.
+fn call_specifier<'t, 'a>(tokens: &'t [Lexeme<'a>], callee: usize) -> Option<&'t Lexeme<'a>> {
+    let mut following = tokens[callee + 1..].iter();
+    if following.next()?.token != Token::Punct(b'(') {
+        return None;
+    }
+    let specifier = following.next()?;
+    if !matches!(specifier.token, Token::Str(_) | Token::Template(Some(_))) {
+        return None;
+    }
+    // A trailing comma or an options argument may follow the specifier.
+    match following.next()?.token {
+        Token::Punct(b')' | b',') => Some(specifier),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+fn extract_specifiers(source: &str) -> Vec<String> {
+    extract_imports(source)
+        .into_iter()
+        .map(|import| import.specifier)
+        .collect()
 }
 
 #[test]
@@ -58,13 +648,168 @@ fn test_extract_import_paths() {
             "import a from \"@double-quote/import\";",
             Some("@double-quote/import"),
         ),
+        ("import a from 'no-semicolon'", Some("no-semicolon")),
+        ("import { from } from 'named-from';", Some("named-from")),
+        ("import a from 'escaped\\'quote';", Some("escaped'quote")),
+        ("import a, { b as c } from 'mixed';", Some("mixed")),
         ("function test() {", None),
+        ("// import a from 'line-comment';", None),
+        ("/* import a from 'block-comment'; */", None),
+        ("const s = \"import a from 'string';\";", None),
+        ("const t = `\nimport a from 'template';\n`;", None),
+        ("const r = /import a from 'regex'/;", None),
+        ("const m = import.meta.url;", None),
+        ("const o = { import: 'property' };", None),
+        ("const d = await import('dynamic');", Some("dynamic")),
+        (
+            "const d = import(`template-dynamic`);",
+            Some("template-dynamic"),
+        ),
+        (
+            "const d = import('with-options', { with: {} });",
+            Some("with-options"),
+        ),
+        ("const d = import(`${computed}`);", None),
+        ("const d = import('concatenated' + suffix);", None),
+        ("const r = require('required');", Some("required")),
+        (
+            "import r = require('import-equals');",
+            Some("import-equals"),
+        ),
+        ("const r = module.require('property');", None),
+        (
+            "import { \"a b\" as x, 'c' as c } from './string-named';",
+            Some("./string-named"),
+        ),
+        ("export * from 'star';", Some("star")),
+        ("export * as ns from 'namespace';", Some("namespace")),
+        ("export {\n  a,\n  b as c,\n} from 'named'", Some("named")),
+        ("export type { T } from 'type-named';", Some("type-named")),
+        ("export { a, b };", None),
+        ("export const from = 'not-reexport';", None),
+        ("export default 'value';", None),
+    ];
+    for (input, expected) in cases {
+        assert_eq!(
+            extract_specifiers(input),
+            expected.map(String::from).into_iter().collect::<Vec<_>>(),
+            "Failed on input: {input}"
+        );
+    }
+}
+
+#[test]
+fn test_extract_imports_positions() {
+    let source =
+        "import a from 'a';\nconst x = `${`${'}'}`}`;\n\nimport {\n  b,\n} from 'b'\nimport 'c';";
+    assert_eq!(
+        extract_imports(source),
+        vec![
+            TsImport {
+                specifier: "a".to_string(),
+                kind: ImportKind::Static,
+                is_type_only: false,
+                offset: 0,
+                line: 1,
+                specifier_line: 1,
+                column: 1,
+                span: 0..17,
+            },
+            TsImport {
+                specifier: "b".to_string(),
+                kind: ImportKind::Static,
+                is_type_only: false,
+                offset: 45,
+                line: 4,
+                specifier_line: 6,
+                column: 1,
+                span: 45..69,
+            },
+            TsImport {
+                specifier: "c".to_string(),
+                kind: ImportKind::Static,
+                is_type_only: false,
+                offset: 70,
+                line: 7,
+                specifier_line: 7,
+                column: 1,
+                span: 70..80,
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_extract_imports_column() {
+    let imports = extract_imports("const é = 1; const d = import('d');");
+    assert_eq!(imports[0].line, 1);
+    assert_eq!(imports[0].column, 24);
+    assert_eq!(imports[0].span, 24..34);
+}
+
+#[test]
+fn test_extract_import_kinds() {
+    let source = "import a from 'a';\nconst b = () => import('b');\nimport c = require('c');\nexport * from 'd';";
+    let kinds = extract_imports(source)
+        .into_iter()
+        .map(|import| (import.specifier, import.kind))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        kinds,
+        vec![
+            ("a".to_string(), ImportKind::Static),
+            ("b".to_string(), ImportKind::Dynamic),
+            ("c".to_string(), ImportKind::Require),
+            ("d".to_string(), ImportKind::ReExport),
+        ]
+    );
+}
+
+#[test]
+fn test_extract_type_only_imports() {
+    let cases = [
+        ("import type { A } from 'foo';", true),
+        ("import type A from 'foo';", true),
+        ("import type * as ns from 'foo';", true),
+        ("import { type A, type B as C } from 'foo';", true),
+        ("export type { A } from 'foo';", true),
+        ("export type * from 'foo';", true),
+        ("export { type A } from 'foo';", true),
+        ("import type A = require('foo');", true),
+        ("type T = import('foo').T;", true),
+        ("let x: typeof import('foo');", true),
+        ("function f(a: import('foo').A) {}", true),
+        ("const m = new Map<string, import('foo').A>();", true),
+        ("let a: string | import('foo').A;", true),
+        ("export import type A = require('foo');", true),
+        ("import { type A, B } from 'foo';", false),
+        ("import { type } from 'foo';", false),
+        ("import { type as t } from 'foo';", false),
+        ("import type from 'foo';", false),
+        ("import type, { A } from 'foo';", false),
+        ("import A = require('foo');", false),
+        ("import type = require('foo');", false),
+        ("const A = require('foo');", false),
+        ("import {} from 'foo';", false),
+        ("import A from 'foo';", false),
+        ("export * from 'foo';", false),
+        ("const a = import('foo');", false),
+        ("const a = { m: import('foo') };", false),
+        ("const a = b ? c : import('foo').then(load);", false),
+        ("type = import('foo');", false),
     ];
     for (input, expected) in cases {
+        let imports = extract_imports(input);
+        assert_eq!(imports.len(), 1, "Failed on input: {input}");
         assert_eq!(
-            extract_import(input),
-            expected.map(String::from),
+            imports[0].is_type_only, expected,
             "Failed on input: {input}"
         );
     }
 }
+
+#[test]
+fn test_extract_imports_ignore_comment() {
+    let source = "// ts_deplint ignore\nimport {\n  a,\n} from 'a';\nimport b from 'b';\n/* ts_deplint ignore */ import c from 'c';";
+    assert_eq!(extract_specifiers(source), vec!["b", "c"]);
+}
@@ -0,0 +1,339 @@
+use std::{
+    collections::BTreeMap,
+    error::Error,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde::Deserialize;
+
+pub const TSCONFIG_FILE_NAME: &str = "tsconfig.json";
+
+// Guards against `extends` cycles.
+const MAX_EXTENDS_DEPTH: usize = 32;
+
+/// The module resolution settings of a tsconfig.json file, with its
+/// `extends` chain applied.
+#[derive(Debug, Default, PartialEq)]
+pub struct TsConfig {
+    /// Absolute directory that non-relative imports are resolved from.
+    pub base_url: Option<PathBuf>,
+    /// Absolute directory that `paths` substitutions are relative to.
+    pub paths_base: PathBuf,
+    /// Path patterns, like `@billing/*`, mapped to their substitutions.
+    pub paths: BTreeMap<String, Vec<String>>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+struct RawTsConfig {
+    extends: Option<Extends>,
+    #[serde(default)]
+    compiler_options: RawCompilerOptions,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Extends {
+    One(String),
+    Many(Vec<String>),
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+struct RawCompilerOptions {
+    base_url: Option<String>,
+    paths: Option<BTreeMap<String, Vec<String>>>,
+}
+
+impl TsConfig {
+    /// Returns the absolute paths that the passed-in non-relative specifier
+    /// may refer to according to `paths`, in order of preference.
+    pub fn resolve_paths_alias(&self, specifier: &str) -> Vec<PathBuf> {
+        // TypeScript picks the pattern with the longest prefix before `*`.
+        let mut best_match: Option<(usize, &Vec<String>, &str)> = None;
+        for (pattern, substitutions) in &self.paths {
+            let (prefix_len, wildcard) = match pattern.split_once('*') {
+                Some((prefix, suffix)) => {
+                    if specifier.len() < prefix.len() + suffix.len()
+                        || !specifier.starts_with(prefix)
+                        || !specifier.ends_with(suffix)
+                    {
+                        continue;
+                    }
+                    (
+                        prefix.len(),
+                        &specifier[prefix.len()..specifier.len() - suffix.len()],
+                    )
+                }
+                None if pattern == specifier => (usize::MAX, ""),
+                None => continue,
+            };
+            if best_match.is_none_or(|(best_len, _, _)| prefix_len > best_len) {
+                best_match = Some((prefix_len, substitutions, wildcard));
+            }
+        }
+        let Some((_, substitutions, wildcard)) = best_match else {
+            return vec![];
+        };
+        substitutions
+            .iter()
+            .map(|substitution| {
+                self.paths_base
+                    .join(substitution.replacen('*', wildcard, 1))
+            })
+            .collect()
+    }
+}
+
+/// Returns the path of the tsconfig.json file nearest to the passed-in
+/// directory, without leaving the root directory.
+pub fn find_tsconfig(start: &Path, root: &Path) -> Option<PathBuf> {
+    start
+        .ancestors()
+        .take_while(|dir| dir.starts_with(root))
+        .map(|dir| dir.join(TSCONFIG_FILE_NAME))
+        .find(|path| path.is_file())
+}
+
+pub fn read_tsconfig(path: &Path) -> Result<TsConfig, Box<dyn Error>> {
+    read_tsconfig_impl(path, 0)
+}
+
+fn read_tsconfig_impl(path: &Path, depth: usize) -> Result<TsConfig, Box<dyn Error>> {
+    if depth > MAX_EXTENDS_DEPTH {
+        return Err(format!("tsconfig 'extends' chain of {:?} is too deep.", path).into());
+    }
+    let content = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read tsconfig {:?}: {}", path, e))?;
+    let raw: RawTsConfig = serde_json::from_str(&strip_jsonc(&content))
+        .map_err(|e| format!("Failed to parse tsconfig {:?}: {}", path, e))?;
+    let dir = path.parent().unwrap_or(Path::new(""));
+
+    let mut config = TsConfig {
+        paths_base: dir.to_path_buf(),
+        ..TsConfig::default()
+    };
+    let extends = match raw.extends {
+        Some(Extends::One(extends)) => vec![extends],
+        Some(Extends::Many(extends)) => extends,
+        None => vec![],
+    };
+    // Later entries of `extends` override earlier ones.
+    for extends in extends {
+        // Presets that aren't installed, like `@tsconfig/node18`, are
+        // skipped rather than failing the lint.
+        let Some(extended_path) = resolve_extends(dir, &extends) else {
+            continue;
+        };
+        let extended = read_tsconfig_impl(&extended_path, depth + 1)?;
+        if extended.base_url.is_some() {
+            config.base_url = extended.base_url;
+        }
+        if !extended.paths.is_empty() {
+            config.paths = extended.paths;
+            config.paths_base = extended.paths_base;
+        }
+    }
+
+    let options = raw.compiler_options;
+    if let Some(base_url) = options.base_url {
+        config.base_url = Some(dir.join(base_url));
+    }
+    if let Some(paths) = options.paths {
+        config.paths = paths;
+        config.paths_base = dir.to_path_buf();
+    }
+    // `paths` are relative to `baseUrl` when one is set.
+    if let Some(base_url) = &config.base_url {
+        config.paths_base = base_url.clone();
+    }
+    Ok(config)
+}
+
+/// Returns the path of the file referenced by an `extends` entry, which is
+/// either a path relative to the extending file or a package.
+fn resolve_extends(dir: &Path, extends: &str) -> Option<PathBuf> {
+    let candidates = if extends.starts_with('.') || Path::new(extends).is_absolute() {
+        let path = dir.join(extends);
+        vec![with_json_extension(&path), path]
+    } else {
+        dir.ancestors()
+            .map(|ancestor| ancestor.join("node_modules").join(extends))
+            .flat_map(|path| {
+                [
+                    path.join(TSCONFIG_FILE_NAME),
+                    with_json_extension(&path),
+                    path,
+                ]
+            })
+            .collect()
+    };
+    candidates.into_iter().find(|path| path.is_file())
+}
+
+fn with_json_extension(path: &Path) -> PathBuf {
+    let mut path = path.as_os_str().to_owned();
+    path.push(".json");
+    PathBuf::from(path)
+}
+
+/// Strips comments and trailing commas, which tsconfig.json files allow,
+/// from JSON.
+fn strip_jsonc(content: &str) -> String {
+    let mut output = String::with_capacity(content.len());
+    let mut chars = content.chars().peekable();
+    let mut in_string = false;
+    while let Some(c) = chars.next() {
+        if in_string {
+            output.push(c);
+            match c {
+                '\\' => output.extend(chars.next()),
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match (c, chars.peek()) {
+            ('"', _) => {
+                in_string = true;
+                output.push(c);
+            }
+            ('/', Some('/')) => {
+                while chars.peek().is_some_and(|c| *c != '\n') {
+                    chars.next();
+                }
+            }
+            ('/', Some('*')) => {
+                chars.next();
+                let mut prev = ' ';
+                for c in chars.by_ref() {
+                    if prev == '*' && c == '/' {
+                        break;
+                    }
+                    prev = c;
+                }
+            }
+            (',', _) => {
+                // Drop the comma if only whitespace and comments separate it
+                // from a closing bracket.
+                if !matches!(next_significant_char(chars.clone()), Some('}' | ']')) {
+                    output.push(c);
+                }
+            }
+            _ => output.push(c),
+        }
+    }
+    output
+}
+
+/// Returns the next character that isn't whitespace or part of a comment.
+fn next_significant_char(mut chars: impl Iterator<Item = char>) -> Option<char> {
+    let mut in_line_comment = false;
+    let mut in_block_comment = false;
+    let mut prev = ' ';
+    while let Some(c) = chars.next() {
+        if in_line_comment {
+            in_line_comment = c != '\n';
+        } else if in_block_comment {
+            in_block_comment = !(prev == '*' && c == '/');
+        } else if c == '/' {
+            match chars.next() {
+                Some('/') => in_line_comment = true,
+                Some('*') => in_block_comment = true,
+                _ => return Some(c),
+            }
+        } else if !c.is_whitespace() {
+            return Some(c);
+        }
+        prev = c;
+    }
+    None
+}
+
+#[test]
+fn test_strip_jsonc() {
+    assert_eq!(
+        strip_jsonc("{\n  // comment\n  \"a\": \"//not/*comment*/\", /* comment */\n  \"b\": [1, 2, /* x */],\n}"),
+        "{\n  \n  \"a\": \"//not/*comment*/\", \n  \"b\": [1, 2 ]\n}"
+    );
+}
+
+#[test]
+fn test_resolve_paths_alias() {
+    let config = TsConfig {
+        base_url: None,
+        paths_base: PathBuf::from("/repo"),
+        paths: BTreeMap::from([
+            ("@billing/*".to_string(), vec!["src/billing/*".to_string()]),
+            (
+                "@billing/ledger/*".to_string(),
+                vec!["src/ledger/*".to_string(), "vendor/ledger/*".to_string()],
+            ),
+            (
+                "~config".to_string(),
+                vec!["src/config/index.ts".to_string()],
+            ),
+        ]),
+    };
+    assert_eq!(
+        config.resolve_paths_alias("@billing/invoices"),
+        vec![PathBuf::from("/repo/src/billing/invoices")]
+    );
+    assert_eq!(
+        config.resolve_paths_alias("@billing/ledger/entries"),
+        vec![
+            PathBuf::from("/repo/src/ledger/entries"),
+            PathBuf::from("/repo/vendor/ledger/entries")
+        ]
+    );
+    assert_eq!(
+        config.resolve_paths_alias("~config"),
+        vec![PathBuf::from("/repo/src/config/index.ts")]
+    );
+    assert!(config.resolve_paths_alias("lodash").is_empty());
+}
+
+#[test]
+fn test_read_tsconfig_with_extends() {
+    let dir = crate::test_utils::TempDir::new("tsconfig");
+    for (file, content) in [
+        (
+            "node_modules/@loop/tsconfig/tsconfig.json",
+            r#"{"compilerOptions": {"paths": {"@pkg/*": ["pkg/*"]}}}"#,
+        ),
+        (
+            "configs/base.json",
+            "{\n  // Shared by every app.\n  \"extends\": \"@loop/tsconfig\",\n  \"compilerOptions\": {\"baseUrl\": \"..\"},\n}",
+        ),
+        (
+            "app/tsconfig.json",
+            r#"{"extends": ["@tsconfig/node18/tsconfig.json", "../configs/base"], "compilerOptions": {"paths": {"@a/*": ["src/a/*"]}}}"#,
+        ),
+        ("cycle/a.json", r#"{"extends": "./b"}"#),
+        ("cycle/b.json", r#"{"extends": "./a.json"}"#),
+    ] {
+        dir.write(file, content);
+    }
+
+    let base_url = dir.join("configs").join("..");
+    assert_eq!(
+        read_tsconfig(&dir.join("configs/base.json")).unwrap(),
+        TsConfig {
+            base_url: Some(base_url.clone()),
+            paths_base: base_url.clone(),
+            paths: BTreeMap::from([("@pkg/*".to_string(), vec!["pkg/*".to_string()])]),
+        }
+    );
+    // `baseUrl` is relative to the file declaring it, and `paths` to `baseUrl`.
+    let base_url = dir.join("app/../configs/..");
+    assert_eq!(
+        read_tsconfig(&dir.join("app/tsconfig.json")).unwrap(),
+        TsConfig {
+            base_url: Some(base_url.clone()),
+            paths_base: base_url,
+            paths: BTreeMap::from([("@a/*".to_string(), vec!["src/a/*".to_string()])]),
+        }
+    );
+    assert!(read_tsconfig(&dir.join("cycle/a.json")).is_err());
+}
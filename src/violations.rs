@@ -1,12 +1,45 @@
 use std::{
     collections::{BTreeSet, HashMap, HashSet},
     hash::{Hash, Hasher},
+    ops::Range,
 };
 
+use crate::{environment::Environment, ts_reader::ImportKind};
+
 #[derive(Debug, Eq, Hash, PartialEq)]
 pub enum Violation {
     DisallowedImportViolation(DisallowedImportViolation),
     ReferenceToNonexistentDirectory(ReferenceToNonexistentDirectory),
+    SuppressionWithoutReason(SuppressionWithoutReason),
+    UnusedSuppression(UnusedSuppression),
+    UnresolvedImport(UnresolvedImport),
+    ImportEscapesRoot(ImportEscapesRoot),
+    DisallowedPackageViolation(DisallowedPackageViolation),
+    DisallowedBuiltinViolation(DisallowedBuiltinViolation),
+    UndeclaredDependency(UndeclaredDependency),
+    UnusedDependency(UnusedDependency),
+    NonExportedImport(NonExportedImport),
+    VisibilityViolation(VisibilityViolation),
+}
+
+impl Violation {
+    /// Returns whether the `fix` command can resolve the violation.
+    pub fn is_fixable(&self) -> bool {
+        match self {
+            Violation::DisallowedImportViolation(_) => true,
+            Violation::ReferenceToNonexistentDirectory(_) => true,
+            Violation::SuppressionWithoutReason(_) => false,
+            Violation::UnusedSuppression(_) => true,
+            Violation::UnresolvedImport(_) => false,
+            Violation::ImportEscapesRoot(_) => false,
+            Violation::DisallowedPackageViolation(_) => false,
+            Violation::DisallowedBuiltinViolation(_) => false,
+            Violation::UndeclaredDependency(_) => false,
+            Violation::UnusedDependency(_) => false,
+            Violation::NonExportedImport(_) => false,
+            Violation::VisibilityViolation(_) => false,
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -14,6 +47,12 @@ pub struct DisallowedImportViolation {
     pub file_path: String,
     pub disallowed_import: String,
     pub full_disallowed_import: String,
+    /// The file that the import resolves to, relative to the root directory.
+    pub resolved_file: Option<String>,
+    pub import_kind: ImportKind,
+    pub is_type_only: bool,
+    pub line: usize,
+    pub column: usize,
 }
 
 impl Hash for DisallowedImportViolation {
@@ -21,6 +60,11 @@ impl Hash for DisallowedImportViolation {
         self.file_path.hash(state);
         self.disallowed_import.hash(state);
         self.full_disallowed_import.hash(state);
+        self.resolved_file.hash(state);
+        self.import_kind.hash(state);
+        self.is_type_only.hash(state);
+        self.line.hash(state);
+        self.column.hash(state);
     }
 }
 
@@ -29,6 +73,11 @@ impl PartialEq for DisallowedImportViolation {
         self.file_path == other.file_path
             && self.disallowed_import == other.disallowed_import
             && self.full_disallowed_import == other.full_disallowed_import
+            && self.resolved_file == other.resolved_file
+            && self.import_kind == other.import_kind
+            && self.is_type_only == other.is_type_only
+            && self.line == other.line
+            && self.column == other.column
     }
 }
 
@@ -41,6 +90,112 @@ pub struct ReferenceToNonexistentDirectory {
     pub directory_name: String,
 }
 
+#[derive(Debug, Eq, Hash, PartialEq)]
+pub struct SuppressionWithoutReason {
+    pub file_path: String,
+    pub line: usize,
+}
+
+/// A suppression comment that doesn't suppress any violation.
+#[derive(Debug, Eq, Hash, PartialEq)]
+pub struct UnusedSuppression {
+    pub file_path: String,
+    pub line: usize,
+    /// Byte range of the comment within the file.
+    pub span: Range<usize>,
+    /// Byte range of the `ts_deplint-enable` comment ending the suppression,
+    /// unless it also ends other suppressions.
+    pub end_span: Option<Range<usize>>,
+}
+
+/// A relative or root-relative import that doesn't resolve to any file.
+#[derive(Debug, Eq, Hash, PartialEq)]
+pub struct UnresolvedImport {
+    pub file_path: String,
+    pub specifier: String,
+    pub line: usize,
+    pub column: usize,
+}
+
+/// An import of a module outside of the package root that isn't listed in
+/// `allow_external`.
+#[derive(Debug, Eq, Hash, PartialEq)]
+pub struct ImportEscapesRoot {
+    pub file_path: String,
+    pub specifier: String,
+    /// Absolute path of the imported module.
+    pub escaped_path: String,
+    pub line: usize,
+    pub column: usize,
+}
+
+/// An import of a third-party package that the `packages` rules of the
+/// importing directory don't allow.
+#[derive(Debug, Eq, Hash, PartialEq)]
+pub struct DisallowedPackageViolation {
+    pub file_path: String,
+    pub package: String,
+    pub specifier: String,
+    pub line: usize,
+    pub column: usize,
+}
+
+/// An import of a Node.js builtin that isn't available in the environment
+/// of the importing directory.
+#[derive(Debug, Eq, Hash, PartialEq)]
+pub struct DisallowedBuiltinViolation {
+    pub file_path: String,
+    pub specifier: String,
+    pub environment: Environment,
+    pub line: usize,
+    pub column: usize,
+}
+
+/// An import of a package that the nearest package.json doesn't declare,
+/// or only declares as a devDependency outside of tests.
+#[derive(Debug, Eq, Hash, PartialEq)]
+pub struct UndeclaredDependency {
+    pub file_path: String,
+    pub package: String,
+    /// Path of the package.json file, relative to the root directory.
+    pub package_json_path: String,
+    pub is_dev_dependency: bool,
+    pub line: usize,
+    pub column: usize,
+}
+
+/// A package in the `dependencies` of a package.json file that none of its
+/// files import.
+#[derive(Debug, Eq, Hash, PartialEq)]
+pub struct UnusedDependency {
+    /// Path of the package.json file, relative to the root directory.
+    pub file_path: String,
+    pub package: String,
+}
+
+/// A deep import of a workspace package that its `exports` don't include.
+#[derive(Debug, Eq, Hash, PartialEq)]
+pub struct NonExportedImport {
+    pub file_path: String,
+    pub specifier: String,
+    pub package: String,
+    pub line: usize,
+    pub column: usize,
+}
+
+/// An import of a directory whose `visibility` doesn't include the
+/// importing file.
+#[derive(Debug, Eq, Hash, PartialEq)]
+pub struct VisibilityViolation {
+    pub file_path: String,
+    pub specifier: String,
+    /// The directory declaring the `visibility`, relative to the root
+    /// directory.
+    pub directory: String,
+    pub line: usize,
+    pub column: usize,
+}
+
 pub fn pretty_print_violations<I>(violations: I)
 where
     I: IntoIterator<Item = Violation>,
@@ -50,10 +205,17 @@ where
     let mut disallowed_imports_by_file_path: HashMap<String, HashSet<String>> = HashMap::new();
     let mut full_disallowed_imports_by_file_path_plus_disallowed_import: HashMap<
         String,
-        HashSet<String>,
+        HashSet<(usize, usize, String)>,
     > = HashMap::new();
     let mut references_to_nonexistent_directories: HashMap<String, HashSet<String>> =
         HashMap::new();
+    let mut suppressions_without_reason_by_file_path: HashMap<String, BTreeSet<usize>> =
+        HashMap::new();
+    let mut unused_suppressions_by_file_path: HashMap<String, BTreeSet<usize>> = HashMap::new();
+    let mut unused_dependencies_by_file_path: HashMap<String, BTreeSet<String>> = HashMap::new();
+    // Issues with single imports, as line, column and message.
+    let mut import_issues_by_file_path: HashMap<String, BTreeSet<(usize, usize, String)>> =
+        HashMap::new();
 
     for violation in violations {
         match violation {
@@ -64,10 +226,20 @@ where
                     .entry(violation.file_path)
                     .or_default()
                     .insert(violation.disallowed_import);
+                let mut full_disallowed_import = violation.full_disallowed_import;
+                if let Some(resolved_file) = violation.resolved_file {
+                    full_disallowed_import =
+                        format!("{} -> {}", full_disallowed_import, resolved_file);
+                }
+                let full_disallowed_import = match (violation.import_kind, violation.is_type_only) {
+                    (ImportKind::Static, false) => full_disallowed_import,
+                    (kind, false) => format!("{} ({})", full_disallowed_import, kind.label()),
+                    (kind, true) => format!("{} (type {})", full_disallowed_import, kind.label()),
+                };
                 full_disallowed_imports_by_file_path_plus_disallowed_import
                     .entry(key)
                     .or_default()
-                    .insert(violation.full_disallowed_import);
+                    .insert((violation.line, violation.column, full_disallowed_import));
             }
             Violation::ReferenceToNonexistentDirectory(issue) => {
                 files_with_violation.insert(issue.file_path.clone());
@@ -76,6 +248,109 @@ where
                     .or_default()
                     .insert(issue.directory_name);
             }
+            Violation::SuppressionWithoutReason(issue) => {
+                files_with_violation.insert(issue.file_path.clone());
+                suppressions_without_reason_by_file_path
+                    .entry(issue.file_path)
+                    .or_default()
+                    .insert(issue.line);
+            }
+            Violation::UnusedSuppression(issue) => {
+                files_with_violation.insert(issue.file_path.clone());
+                unused_suppressions_by_file_path
+                    .entry(issue.file_path)
+                    .or_default()
+                    .insert(issue.line);
+            }
+            Violation::UnusedDependency(issue) => {
+                files_with_violation.insert(issue.file_path.clone());
+                unused_dependencies_by_file_path
+                    .entry(issue.file_path)
+                    .or_default()
+                    .insert(issue.package);
+            }
+            Violation::NonExportedImport(issue) => {
+                files_with_violation.insert(issue.file_path.clone());
+                let message = format!(
+                    "imports '{}', which '{}' doesn't export",
+                    issue.specifier, issue.package
+                );
+                import_issues_by_file_path
+                    .entry(issue.file_path)
+                    .or_default()
+                    .insert((issue.line, issue.column, message));
+            }
+            Violation::VisibilityViolation(issue) => {
+                files_with_violation.insert(issue.file_path.clone());
+                let message = format!(
+                    "imports '{}', but '{}' isn't visible to it",
+                    issue.specifier, issue.directory
+                );
+                import_issues_by_file_path
+                    .entry(issue.file_path)
+                    .or_default()
+                    .insert((issue.line, issue.column, message));
+            }
+            Violation::UnresolvedImport(issue) => {
+                files_with_violation.insert(issue.file_path.clone());
+                let message = format!("cannot resolve '{}'", issue.specifier);
+                import_issues_by_file_path
+                    .entry(issue.file_path)
+                    .or_default()
+                    .insert((issue.line, issue.column, message));
+            }
+            Violation::ImportEscapesRoot(issue) => {
+                files_with_violation.insert(issue.file_path.clone());
+                let message = format!(
+                    "import '{}' escapes package root to {}",
+                    issue.specifier, issue.escaped_path
+                );
+                import_issues_by_file_path
+                    .entry(issue.file_path)
+                    .or_default()
+                    .insert((issue.line, issue.column, message));
+            }
+            Violation::DisallowedPackageViolation(violation) => {
+                files_with_violation.insert(violation.file_path.clone());
+                let mut message = format!("imports disallowed package '{}'", violation.package);
+                if violation.specifier != violation.package {
+                    message = format!("{} ({})", message, violation.specifier);
+                }
+                import_issues_by_file_path
+                    .entry(violation.file_path)
+                    .or_default()
+                    .insert((violation.line, violation.column, message));
+            }
+            Violation::DisallowedBuiltinViolation(violation) => {
+                files_with_violation.insert(violation.file_path.clone());
+                let message = format!(
+                    "imports Node.js builtin '{}' in {} code",
+                    violation.specifier,
+                    violation.environment.label()
+                );
+                import_issues_by_file_path
+                    .entry(violation.file_path)
+                    .or_default()
+                    .insert((violation.line, violation.column, message));
+            }
+            Violation::UndeclaredDependency(violation) => {
+                files_with_violation.insert(violation.file_path.clone());
+                let message = if violation.is_dev_dependency {
+                    format!(
+                        "imports '{}', which {} only declares in devDependencies",
+                        violation.package, violation.package_json_path
+                    )
+                } else {
+                    format!(
+                        "imports '{}', which {} doesn't declare",
+                        violation.package, violation.package_json_path
+                    )
+                };
+                import_issues_by_file_path
+                    .entry(violation.file_path)
+                    .or_default()
+                    .insert((violation.line, violation.column, message));
+            }
         }
     }
 
@@ -95,8 +370,11 @@ where
                     sorted_full_disallowed_imports.sort();
                     sorted_full_disallowed_imports
                 };
-                for full_disallowed_import in sorted_full_disallowed_imports {
-                    println!("     {}", full_disallowed_import);
+                for (line, column, full_disallowed_import) in sorted_full_disallowed_imports {
+                    println!(
+                        "     {}:{}:{} {}",
+                        file_path, line, column, full_disallowed_import
+                    );
                 }
             }
             println!();
@@ -106,5 +384,28 @@ where
                 println!("  references nonexistent '{}'", missing_directory);
             }
         }
+        if let Some(lines) = suppressions_without_reason_by_file_path.get(&file_path) {
+            for line in lines {
+                println!(
+                    "  {}:{} suppression is missing a '-- reason'",
+                    file_path, line
+                );
+            }
+        }
+        if let Some(lines) = unused_suppressions_by_file_path.get(&file_path) {
+            for line in lines {
+                println!("  {}:{} suppression is unused", file_path, line);
+            }
+        }
+        if let Some(packages) = unused_dependencies_by_file_path.get(&file_path) {
+            for package in packages {
+                println!("  declares unused dependency '{}'", package);
+            }
+        }
+        if let Some(issues) = import_issues_by_file_path.get(&file_path) {
+            for (line, column, message) in issues {
+                println!("  {}:{}:{} {}", file_path, line, column, message);
+            }
+        }
     }
 }
@@ -0,0 +1,95 @@
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    path::{Path, PathBuf},
+    rc::Rc,
+};
+
+use crate::{
+    glob::glob_match,
+    rules::{read_rules_file, RULES_FILE_NAME},
+};
+
+/// Caches the `visibility` of the directories that imports resolve to, which
+/// lie anywhere within the root directory.
+#[derive(Default)]
+pub struct VisibilityCache {
+    by_directory: RefCell<HashMap<PathBuf, Option<Rc<Vec<String>>>>>,
+}
+
+impl VisibilityCache {
+    /// Returns the directory, relative to the root directory, whose
+    /// `visibility` doesn't include the importing file, if any. Both paths
+    /// are relative to the root directory.
+    pub fn find_invisible_directory(
+        &self,
+        root: &Path,
+        importing_file: &Path,
+        import_path: &Path,
+    ) -> Option<PathBuf> {
+        let importing_directory = importing_file.parent()?.to_str()?;
+        for directory in import_path.ancestors().skip(1) {
+            if directory.as_os_str().is_empty() || importing_file.starts_with(directory) {
+                // Directories may always import their own descendants, and so
+                // may their ancestors' descendants.
+                break;
+            }
+            let Some(visibility) = self.get(root, directory) else {
+                continue;
+            };
+            if !visibility
+                .iter()
+                .any(|pattern| glob_match(pattern, importing_directory))
+            {
+                return Some(directory.to_path_buf());
+            }
+        }
+        None
+    }
+
+    fn get(&self, root: &Path, directory: &Path) -> Option<Rc<Vec<String>>> {
+        if let Some(visibility) = self.by_directory.borrow().get(directory) {
+            return visibility.clone();
+        }
+        let rules_path = root.join(directory).join(RULES_FILE_NAME);
+        let visibility = read_rules_file(&rules_path)
+            .ok()
+            .and_then(|rules| rules.visibility)
+            .map(Rc::new);
+        self.by_directory
+            .borrow_mut()
+            .insert(directory.to_path_buf(), visibility.clone());
+        visibility
+    }
+}
+
+#[test]
+fn test_find_invisible_directory() {
+    let dir = crate::test_utils::TempDir::new("visibility");
+    dir.create_dirs(&["src/ledger/internal"]);
+    dir.write(
+        &format!("src/ledger/{}", RULES_FILE_NAME),
+        "visibility: ['src/app/**', src/jobs]\n",
+    );
+
+    let cache = VisibilityCache::default();
+    let find = |importing_file: &str| {
+        cache.find_invisible_directory(
+            &dir,
+            Path::new(importing_file),
+            Path::new("src/ledger/internal/entries.ts"),
+        )
+    };
+    assert_eq!(find("src/app/main.ts"), None);
+    assert_eq!(find("src/app/pages/home.ts"), None);
+    assert_eq!(find("src/jobs/nightly.ts"), None);
+    assert_eq!(find("src/ledger/index.ts"), None);
+    assert_eq!(
+        find("src/jobs/cleanup/run.ts"),
+        Some(PathBuf::from("src/ledger"))
+    );
+    assert_eq!(
+        find("src/billing/invoice.ts"),
+        Some(PathBuf::from("src/ledger"))
+    );
+}
@@ -1,161 +1,347 @@
 use crate::{
-    disallowed, files, rules, ts_reader,
-    violations::{DisallowedImportViolation, Violation},
-};
-use std::{
-    error::Error,
-    fs::canonicalize,
-    path::{Path, PathBuf},
+    dependencies::{self, Declaration, DependencyCache},
+    disallowed::{self, DisallowedImports},
+    environment, files,
+    options::LintOptions,
+    package_json::PACKAGE_JSON_FILE_NAME,
+    packages,
+    resolve::{ImportResolver, ResolvedImport},
+    rules::{self, Rules},
+    ts_reader,
+    violations::{
+        DisallowedBuiltinViolation, DisallowedImportViolation, DisallowedPackageViolation,
+        ImportEscapesRoot, NonExportedImport, SuppressionWithoutReason, UndeclaredDependency,
+        UnresolvedImport, UnusedSuppression, Violation, VisibilityViolation,
+    },
+    visibility::VisibilityCache,
 };
+use std::{error::Error, path::Path};
+
+/// State shared by every directory of a traversal.
+pub struct VisitContext<'a> {
+    pub root: &'a Path,
+    pub options: &'a LintOptions,
+    pub resolver: &'a ImportResolver,
+    pub dependencies: &'a DependencyCache,
+    pub visibility: &'a VisibilityCache,
+    pub abort_on_violation: bool,
+}
+
+impl VisitContext<'_> {
+    fn should_abort(&self, violations: &[Violation]) -> bool {
+        self.abort_on_violation && violations.iter().any(Violation::is_fixable)
+    }
+}
 
 pub fn visit_path(
+    context: &VisitContext,
     violations: &mut Vec<Violation>,
-    root: &Path,
-    disallowed_imports: &[String],
+    disallowed_imports: &DisallowedImports,
     current: &Path,
-    abort_on_violation: bool,
 ) -> Result<(), Box<dyn Error>> {
     let files_and_directories = files::list_files_and_directories(current)?;
+    let (current_rules, rules_file_violations) =
+        rules::get_dir_rules_if_exists(context.root, current);
+    violations.extend(
+        rules_file_violations
+            .into_iter()
+            .map(Violation::ReferenceToNonexistentDirectory),
+    );
+    let disallowed_imports =
+        disallowed::get_dir_disallowed_imports(current, disallowed_imports, &current_rules);
+    if context.options.report_unused_dependencies && current.join(PACKAGE_JSON_FILE_NAME).is_file()
+    {
+        // Make sure that packages without any imports are reported too.
+        context.dependencies.get(context.root, current)?;
+    }
 
     check_files_for_disallowed_imports(
+        context,
         violations,
-        root,
-        disallowed_imports,
+        &disallowed_imports,
         current,
         &files_and_directories.files,
-        abort_on_violation,
     )?;
-    if abort_on_violation && !violations.is_empty() {
+    if context.should_abort(violations) {
         return Ok(());
     }
 
     visit_directories(
+        context,
         violations,
-        root,
-        disallowed_imports,
+        &disallowed_imports,
+        &current_rules,
         current,
         &files_and_directories.directories,
-        abort_on_violation,
     )?;
 
     Ok(())
 }
 
 fn check_files_for_disallowed_imports(
+    context: &VisitContext,
     violations: &mut Vec<Violation>,
-    root: &Path,
-    disallowed_imports: &[String],
+    disallowed_imports: &DisallowedImports,
     current: &Path,
     files: &[String],
-    abort_on_violation: bool,
 ) -> Result<(), Box<dyn Error>> {
     for file in files {
-        if !file.ends_with(".ts") {
+        if !context.options.is_source_file(file) {
             continue;
         }
 
         let full_path = current.join(file);
-        let relative_path = full_path.strip_prefix(root)?;
+        let relative_path = full_path.strip_prefix(context.root)?;
+        let file_path = relative_path.to_str().expect("").to_string();
 
-        let imports = ts_reader::read_ts_imports(&full_path)?;
-        for import in imports {
-            let canonicalized_import_path = canonicalize_import_path(&import, root, current)?;
-            for disallowed_import in disallowed_imports {
-                if canonicalized_import_path.starts_with(disallowed_import) {
+        let ts_file = ts_reader::read_ts_file(&full_path)?;
+        let mut suppressions = Vec::with_capacity(ts_file.suppressions.len());
+        for suppression in &ts_file.suppressions {
+            if context.options.require_suppression_reasons && suppression.reason.is_none() {
+                violations.push(Violation::SuppressionWithoutReason(
+                    SuppressionWithoutReason {
+                        file_path: file_path.clone(),
+                        line: suppression.line,
+                    },
+                ));
+            } else {
+                suppressions.push(suppression);
+            }
+        }
+
+        let mut used_suppressions = vec![false; suppressions.len()];
+        for import in ts_file.imports {
+            let resolved_import = context
+                .resolver
+                .resolve_import(&import.specifier, current)?;
+            // Returns whether a suppression applies to the import of the
+            // passed-in path, marking it as used.
+            let mut is_suppressed = |path: &Path| {
+                let suppression_index = suppressions
+                    .iter()
+                    .position(|s| s.suppresses(&import, path));
+                if let Some(index) = suppression_index {
+                    used_suppressions[index] = true;
+                }
+                suppression_index.is_some()
+            };
+            let package = packages::package_name(&import.specifier);
+
+            if let Some(package) = package {
+                let is_package_import = resolved_import == ResolvedImport::External
+                    || context.resolver.is_workspace_package(package);
+                let options = context.options;
+                let checks_dependencies =
+                    options.report_undeclared_dependencies || options.report_unused_dependencies;
+                if is_package_import && !checks_dependencies {
+                    // Whether the package is declared isn't known without
+                    // reading package.json, so count suppressions of it as
+                    // used.
+                    if environment::node_builtin(&import.specifier).is_none() {
+                        is_suppressed(Path::new(package));
+                    }
+                } else if is_package_import {
+                    if let Some(declared) = context.dependencies.get(context.root, current)? {
+                        if options.report_unused_dependencies {
+                            context.dependencies.record_import(
+                                &declared,
+                                &import.specifier,
+                                package,
+                            );
+                        }
+                        let declaration = declared.declaration(package, import.is_type_only);
+                        // Only tests may import devDependencies, since type
+                        // imports of them would leak into emitted declarations.
+                        let is_declared = declaration == Declaration::Dependency
+                            || (declaration == Declaration::DevDependency
+                                && dependencies::is_test_file(relative_path));
+                        if environment::node_builtin(&import.specifier).is_none()
+                            && !is_declared
+                            && !is_suppressed(Path::new(package))
+                            && options.report_undeclared_dependencies
+                        {
+                            violations.push(Violation::UndeclaredDependency(
+                                UndeclaredDependency {
+                                    file_path: file_path.clone(),
+                                    package: package.to_string(),
+                                    package_json_path: declared.package_json_path.clone(),
+                                    is_dev_dependency: declaration == Declaration::DevDependency,
+                                    line: import.line,
+                                    column: import.column,
+                                },
+                            ));
+                        }
+                    }
+                }
+            }
+
+            if let ResolvedImport::NotExported { package } = &resolved_import {
+                if !is_suppressed(Path::new(package)) {
+                    violations.push(Violation::NonExportedImport(NonExportedImport {
+                        file_path: file_path.clone(),
+                        specifier: import.specifier.clone(),
+                        package: package.clone(),
+                        line: import.line,
+                        column: import.column,
+                    }));
+                }
+                continue;
+            }
+            if let ResolvedImport::OutsideRoot(path) = &resolved_import {
+                if !disallowed_imports.allows_external(path) && !is_suppressed(path) {
+                    violations.push(Violation::ImportEscapesRoot(ImportEscapesRoot {
+                        file_path: file_path.clone(),
+                        specifier: import.specifier.clone(),
+                        escaped_path: path.to_str().expect("").to_string(),
+                        line: import.line,
+                        column: import.column,
+                    }));
+                }
+                continue;
+            }
+            if resolved_import == ResolvedImport::External {
+                if let Some(environment) = disallowed_imports.environment {
+                    if environment.disallows(&import.specifier) {
+                        if !is_suppressed(Path::new(&import.specifier)) {
+                            violations.push(Violation::DisallowedBuiltinViolation(
+                                DisallowedBuiltinViolation {
+                                    file_path: file_path.clone(),
+                                    specifier: import.specifier.clone(),
+                                    environment,
+                                    line: import.line,
+                                    column: import.column,
+                                },
+                            ));
+                        }
+                        continue;
+                    }
+                }
+                let Some(package) = package else {
+                    continue;
+                };
+                if !disallowed_imports.allows_package(package) && !is_suppressed(Path::new(package))
+                {
+                    violations.push(Violation::DisallowedPackageViolation(
+                        DisallowedPackageViolation {
+                            file_path: file_path.clone(),
+                            package: package.to_string(),
+                            specifier: import.specifier.clone(),
+                            line: import.line,
+                            column: import.column,
+                        },
+                    ));
+                }
+                continue;
+            }
+            let Some(import_path) = resolved_import.path() else {
+                continue;
+            };
+            if matches!(resolved_import, ResolvedImport::Unresolved(_))
+                && !is_suppressed(import_path)
+                && context.options.report_unresolved_imports
+            {
+                violations.push(Violation::UnresolvedImport(UnresolvedImport {
+                    file_path: file_path.clone(),
+                    specifier: import.specifier.clone(),
+                    line: import.line,
+                    column: import.column,
+                }));
+            }
+            if let Some(directory) = context.visibility.find_invisible_directory(
+                context.root,
+                relative_path,
+                import_path,
+            ) {
+                if !is_suppressed(import_path) {
+                    violations.push(Violation::VisibilityViolation(VisibilityViolation {
+                        file_path: file_path.clone(),
+                        specifier: import.specifier.clone(),
+                        directory: directory.to_str().expect("").to_string(),
+                        line: import.line,
+                        column: import.column,
+                    }));
+                }
+            }
+            for disallowed_import in disallowed_imports.for_import(import.is_type_only) {
+                if import_path.starts_with(disallowed_import) {
+                    if is_suppressed(import_path) {
+                        continue;
+                    }
                     let violation = DisallowedImportViolation {
-                        file_path: relative_path.to_str().expect("").to_string(),
+                        file_path: file_path.clone(),
                         disallowed_import: disallowed_import.clone(),
-                        full_disallowed_import: import.clone(),
+                        full_disallowed_import: import.specifier.clone(),
+                        resolved_file: match &resolved_import {
+                            ResolvedImport::File(path) => path.to_str().map(str::to_string),
+                            _ => None,
+                        },
+                        import_kind: import.kind,
+                        is_type_only: import.is_type_only,
+                        line: import.line,
+                        column: import.column,
                     };
                     violations.push(Violation::DisallowedImportViolation(violation));
-                    if abort_on_violation {
+                    if context.abort_on_violation {
                         return Ok(());
                     }
                 }
             }
         }
+
+        for (suppression, used) in suppressions.iter().zip(used_suppressions) {
+            if !used {
+                // Keep enable comments that also end other suppressions.
+                let end_span = suppression
+                    .end_span()
+                    .filter(|end_span| {
+                        !ts_file.suppressions.iter().any(|other| {
+                            other.span != suppression.span && other.end_span() == Some(end_span)
+                        })
+                    })
+                    .cloned();
+                violations.push(Violation::UnusedSuppression(UnusedSuppression {
+                    file_path: file_path.clone(),
+                    line: suppression.line,
+                    span: suppression.span.clone(),
+                    end_span,
+                }));
+                if context.abort_on_violation {
+                    return Ok(());
+                }
+            }
+        }
     }
 
     Ok(())
 }
 
 fn visit_directories(
+    context: &VisitContext,
     violations: &mut Vec<Violation>,
-    root: &Path,
-    disallowed_imports: &[String],
+    disallowed_imports: &DisallowedImports,
+    current_rules: &Option<Rules>,
     current: &Path,
     directories: &[String],
-    abort_on_violation: bool,
 ) -> Result<(), Box<dyn Error>> {
-    let (current_rules, rules_file_violations) = rules::get_dir_rules_if_exists(root, current);
-    violations.extend(
-        rules_file_violations
-            .into_iter()
-            .map(Violation::ReferenceToNonexistentDirectory),
-    );
     for child in directories {
+        if child == "node_modules" {
+            // Installed packages aren't part of the project's sources.
+            continue;
+        }
         let dir_disallowed_imports = disallowed::get_child_disallowed_imports(
-            root,
+            context.root,
             current,
             disallowed_imports,
-            &current_rules,
+            current_rules,
             child,
+            directories,
         );
         let next = current.join(child);
-        visit_path(
-            violations,
-            root,
-            &dir_disallowed_imports,
-            &next,
-            abort_on_violation,
-        )?;
-        if abort_on_violation && !violations.is_empty() {
+        visit_path(context, violations, &dir_disallowed_imports, &next)?;
+        if context.should_abort(violations) {
             return Ok(());
         }
     }
 
     Ok(())
 }
-
-fn canonicalize_import_path(
-    import: &str,
-    root_directory: &Path,
-    current_directory: &Path,
-) -> Result<PathBuf, Box<dyn Error>> {
-    if import.trim().is_empty() {
-        // Edge case handling. An empty string would lead to us
-        // resolving the root directory, and stripping the
-        // root_directory prefix would fail.
-        return Ok(PathBuf::from(import));
-    }
-
-    let import_path = Path::new(&import);
-    let file_name = import_path.file_name().unwrap_or_default();
-
-    let fully_qualified_path: PathBuf = if import.starts_with(".") {
-        // Relative imports are relative to the current directory of the file.
-        current_directory.join(import_path)
-    } else {
-        // Absolute imports are relative to the root directory of the project.
-        root_directory.join(import_path)
-    };
-
-    // We expect there to always be a parent directory since we
-    // append the import path to a directory.
-    let directory_path = fully_qualified_path
-        .parent()
-        .expect("Unable to get parent directory");
-
-    let Ok(canonicalized_directory_path) = canonicalize(directory_path) else {
-        // If the path doesn't exist, we assume it's an import from a third-party library
-        // and return the original import path as is.
-        return Ok(import_path.to_path_buf());
-    };
-
-    let path_from_root = canonicalized_directory_path
-        .join(file_name)
-        .strip_prefix(root_directory)?
-        .to_path_buf();
-
-    Ok(path_from_root)
-}
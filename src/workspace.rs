@@ -0,0 +1,234 @@
+use std::{
+    collections::BTreeMap,
+    error::Error,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::{
+    files,
+    glob::glob_match,
+    package_json::{
+        self, match_subpath_pattern, select_target, Workspaces, PACKAGE_JSON_FILE_NAME,
+    },
+};
+
+pub const PNPM_WORKSPACE_FILE_NAME: &str = "pnpm-workspace.yaml";
+
+/// The packages of a pnpm, yarn or npm workspace.
+pub struct Workspace {
+    /// Package names mapped to their packages.
+    pub packages: BTreeMap<String, WorkspacePackage>,
+}
+
+pub struct WorkspacePackage {
+    pub directory: PathBuf,
+    /// Subpaths, like `.` or `./features/*`, mapped to their targets, if
+    /// the package restricts its entry points with `exports`.
+    exports: Option<BTreeMap<String, Value>>,
+}
+
+/// What an import of a workspace package refers to.
+#[derive(Debug, PartialEq)]
+pub enum PackageImport {
+    /// Absolute paths that the import may refer to, in order of preference.
+    Paths(Vec<PathBuf>),
+    /// The package's `exports` don't include the imported subpath.
+    NotExported { package: String },
+}
+
+#[derive(Deserialize)]
+struct PnpmWorkspace {
+    #[serde(default)]
+    packages: Vec<String>,
+}
+
+impl Workspace {
+    /// Returns what the passed-in bare specifier refers to if it names a
+    /// workspace package, like `@loop/ledger` or `@loop/ledger/src/entries`.
+    pub fn resolve_package_import(&self, specifier: &str) -> Option<PackageImport> {
+        self.packages.iter().find_map(|(name, package)| {
+            let subpath = if specifier == name {
+                ""
+            } else {
+                specifier.strip_prefix(name)?.strip_prefix('/')?
+            };
+            Some(package.resolve_subpath(name, subpath))
+        })
+    }
+}
+
+impl WorkspacePackage {
+    fn resolve_subpath(&self, name: &str, subpath: &str) -> PackageImport {
+        let source_path = self.directory.join(subpath);
+        let Some(exports) = &self.exports else {
+            return PackageImport::Paths(vec![source_path]);
+        };
+        let export_key = if subpath.is_empty() {
+            ".".to_string()
+        } else {
+            format!("./{}", subpath)
+        };
+        let target = match_subpath_pattern(exports, &export_key)
+            .and_then(|(value, wildcard)| Some(select_target(value)?.replace('*', wildcard)));
+        match target {
+            // Fall back to the source path when the target, like a build
+            // output, doesn't exist.
+            Some(target) => PackageImport::Paths(vec![self.directory.join(target), source_path]),
+            None => PackageImport::NotExported {
+                package: name.to_string(),
+            },
+        }
+    }
+}
+
+/// Returns the `exports` field of a package.json as a map of subpaths to
+/// targets, expanding the shorthand for only exporting `.`.
+fn exports_map(exports: Value) -> BTreeMap<String, Value> {
+    match exports {
+        Value::Object(map) if map.keys().all(|key| key.starts_with('.')) => {
+            map.into_iter().collect()
+        }
+        exports => BTreeMap::from([(".".to_string(), exports)]),
+    }
+}
+
+/// Returns the workspace that the passed-in directory belongs to, found by
+/// walking up to the nearest package.json declaring `workspaces` or
+/// pnpm-workspace.yaml.
+pub fn find_workspace(start: &Path) -> Result<Option<Workspace>, Box<dyn Error>> {
+    for dir in start.ancestors() {
+        let patterns = if dir.join(PNPM_WORKSPACE_FILE_NAME).is_file() {
+            let file = fs::File::open(dir.join(PNPM_WORKSPACE_FILE_NAME))?;
+            let pnpm_workspace: PnpmWorkspace = serde_yaml::from_reader(file)?;
+            pnpm_workspace.packages
+        } else if dir.join(PACKAGE_JSON_FILE_NAME).is_file() {
+            match package_json::read_package_json(dir)?.workspaces {
+                Some(Workspaces::Patterns(patterns)) => patterns,
+                Some(Workspaces::Object { packages }) => packages,
+                None => continue,
+            }
+        } else {
+            continue;
+        };
+        return Ok(Some(Workspace {
+            packages: find_workspace_packages(dir, &patterns)?,
+        }));
+    }
+    Ok(None)
+}
+
+fn find_workspace_packages(
+    root: &Path,
+    patterns: &[String],
+) -> Result<BTreeMap<String, WorkspacePackage>, Box<dyn Error>> {
+    let (excluded, included): (Vec<&str>, Vec<&str>) = patterns
+        .iter()
+        .map(|pattern| pattern.trim_start_matches("./").trim_end_matches('/'))
+        .partition(|pattern| pattern.starts_with('!'));
+    let excluded = excluded
+        .iter()
+        .map(|pattern| &pattern[1..])
+        .collect::<Vec<_>>();
+    let max_depth = if included.iter().any(|pattern| pattern.contains("**")) {
+        usize::MAX
+    } else {
+        included
+            .iter()
+            .map(|pattern| pattern.split('/').count())
+            .max()
+            .unwrap_or(0)
+    };
+
+    let mut packages = BTreeMap::new();
+    let mut to_visit = vec![(root.to_path_buf(), 0)];
+    while let Some((dir, depth)) = to_visit.pop() {
+        let relative = dir.strip_prefix(root)?.to_str().unwrap_or_default();
+        let is_package = !relative.is_empty()
+            && included.iter().any(|p| glob_match(p, relative))
+            && !excluded.iter().any(|p| glob_match(p, relative))
+            && dir.join(PACKAGE_JSON_FILE_NAME).is_file();
+        if is_package {
+            let package_json = package_json::read_package_json(&dir)?;
+            if let Some(name) = package_json.name {
+                let package = WorkspacePackage {
+                    directory: fs::canonicalize(&dir)?,
+                    exports: package_json.exports.map(exports_map),
+                };
+                packages.insert(name, package);
+            }
+        }
+        if depth >= max_depth {
+            continue;
+        }
+        for child in files::list_files_and_directories(&dir)?.directories {
+            if child == "node_modules" || child.starts_with('.') {
+                continue;
+            }
+            to_visit.push((dir.join(child), depth + 1));
+        }
+    }
+    Ok(packages)
+}
+
+#[test]
+fn test_resolve_package_import() {
+    let exports = serde_json::json!({
+        ".": { "types": "./src/index.ts", "default": "./dist/index.js" },
+        "./features/*": "./src/features/*.ts",
+        "./features/internal/*": null
+    });
+    let workspace = Workspace {
+        packages: BTreeMap::from([
+            (
+                "@loop/ledger".to_string(),
+                WorkspacePackage {
+                    directory: PathBuf::from("/repo/packages/ledger"),
+                    exports: Some(exports_map(exports)),
+                },
+            ),
+            (
+                "@loop/billing".to_string(),
+                WorkspacePackage {
+                    directory: PathBuf::from("/repo/packages/billing"),
+                    exports: None,
+                },
+            ),
+        ]),
+    };
+    assert_eq!(
+        workspace.resolve_package_import("@loop/ledger"),
+        Some(PackageImport::Paths(vec![
+            PathBuf::from("/repo/packages/ledger/./src/index.ts"),
+            PathBuf::from("/repo/packages/ledger/"),
+        ]))
+    );
+    assert_eq!(
+        workspace.resolve_package_import("@loop/ledger/features/entries"),
+        Some(PackageImport::Paths(vec![
+            PathBuf::from("/repo/packages/ledger/./src/features/entries.ts"),
+            PathBuf::from("/repo/packages/ledger/features/entries"),
+        ]))
+    );
+    let not_exported = Some(PackageImport::NotExported {
+        package: "@loop/ledger".to_string(),
+    });
+    assert_eq!(
+        workspace.resolve_package_import("@loop/ledger/src/internal/x"),
+        not_exported
+    );
+    assert_eq!(
+        workspace.resolve_package_import("@loop/ledger/features/internal/x"),
+        not_exported
+    );
+    assert_eq!(
+        workspace.resolve_package_import("@loop/billing/src/internal/x"),
+        Some(PackageImport::Paths(vec![PathBuf::from(
+            "/repo/packages/billing/src/internal/x"
+        )]))
+    );
+    assert_eq!(workspace.resolve_package_import("@loop/ledgers"), None);
+}
@@ -23,7 +23,11 @@ mod tests {
         let result = ts_reader::read_ts_imports(&ts_path);
         assert!(result.is_ok());
 
-        let ts_imports = result.unwrap();
+        let ts_imports = result
+            .unwrap()
+            .into_iter()
+            .map(|import| import.specifier)
+            .collect::<Vec<_>>();
         assert_eq!(ts_imports, vec!["foo", "baz/baz/baz", "./baz/bay"]);
     }
 }